@@ -4,37 +4,138 @@
 //! and transition concepts.
 
 use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::hash::Hash;
+use std::str::FromStr;
 use strum::VariantNames;
 use strum_macros::{Display, EnumString, EnumVariantNames, IntoStaticStr};
 
 use log::{debug, info};
 
-// use crate::error::Error;
-use crate::event::EventData;
-use crate::machine::getattr;
+use crate::error::Error;
+use crate::event::{EventArgs, EventData, EventValue};
+use crate::machine::{WILDCARD_ALL, WILDCARD_SAME};
 use crate::Result;
 use append::Append;
-use std::borrow::Borrow;
+use std::rc::Rc;
 // use std::iter::Chain;
 
-pub struct TriggerFunction {
-    function: Box<dyn Fn(&EventData)>,
+/// The shapes a callback can take: it either receives the whole ``EventData`` (used when the
+///     machine's ``send_event`` is True) or only the arguments passed to the trigger (used when
+///     ``send_event`` is False). A callable of the other shape is rejected when it is added to the
+///     machine. Either shape may fail, which stops the processing of the event.
+///     Callbacks referred to by name are looked up in the machine's ``CallbackRegistry`` and
+///     checked when the event is processed.
+#[derive(Clone)]
+#[allow(clippy::type_complexity)]
+enum TriggerCallable<S, E> {
+    Event(Rc<dyn Fn(&EventData<'_, S, E>) -> Result<()>>),
+    Args(Rc<dyn Fn(&EventArgs) -> Result<()>>),
+    Named,
+}
+
+#[derive(Clone)]
+pub struct TriggerFunction<S = String, E = String> {
+    function: TriggerCallable<S, E>,
     name: Option<String>,
 }
 
-impl TriggerFunction {
+impl<S: StateValue, E: EventValue> TriggerFunction<S, E> {
+    /// Create a callback which receives the ``EventData`` of the processed event.
     #[allow(dead_code)]
     pub fn new<F>(f: F, name: Option<String>) -> Self
     where
-        F: Fn(&EventData) + 'static,
+        F: Fn(&EventData<'_, S, E>) + 'static,
+    {
+        Self::fallible(
+            move |event_data| {
+                f(event_data);
+                Ok(())
+            },
+            name,
+        )
+    }
+
+    /// Create a callback which only receives the arguments passed to the trigger.
+    pub fn with_args<F>(f: F, name: Option<String>) -> Self
+    where
+        F: Fn(&EventArgs) + 'static,
+    {
+        Self::fallible_with_args(
+            move |args| {
+                f(args);
+                Ok(())
+            },
+            name,
+        )
+    }
+
+    /// Create a callback which receives the ``EventData`` and may fail. A returned error halts
+    ///     the current transition and is passed on to the machine's ``on_exception`` callbacks.
+    pub fn fallible<F>(f: F, name: Option<String>) -> Self
+    where
+        F: Fn(&EventData<'_, S, E>) -> Result<()> + 'static,
+    {
+        let function = TriggerCallable::Event(Rc::new(f));
+        TriggerFunction { function, name }
+    }
+
+    /// Create a callback which only receives the trigger arguments and may fail.
+    pub fn fallible_with_args<F>(f: F, name: Option<String>) -> Self
+    where
+        F: Fn(&EventArgs) -> Result<()> + 'static,
     {
-        let function = Box::new(f);
+        let function = TriggerCallable::Args(Rc::new(f));
         TriggerFunction { function, name }
     }
 
+    /// Refer to a callback registered in the machine's ``CallbackRegistry``. The name is resolved
+    ///     when the event is processed.
+    pub fn named(name: &str) -> Self {
+        TriggerFunction {
+            function: TriggerCallable::Named,
+            name: Some(name.to_string()),
+        }
+    }
+
+    /// The name of the callback, if one has been given.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Indicates if the callback is only a reference to a callback in the ``CallbackRegistry``.
+    pub fn is_named(&self) -> bool {
+        matches!(self.function, TriggerCallable::Named)
+    }
+
+    pub(crate) fn or_named(mut self, name: &str) -> Self {
+        self.name.get_or_insert_with(|| name.to_string());
+        self
+    }
+
+    /// Indicates if the callback expects the ``EventData`` rather than the trigger arguments.
+    pub fn sends_event(&self) -> bool {
+        matches!(self.function, TriggerCallable::Event(_))
+    }
+
+    /// Fail if the callback does not match the machine's ``send_event``. Callbacks referred to by
+    ///     name can only be checked once they have been resolved.
+    pub(crate) fn check_shape(&self, machine_name: &str, send_event: bool) -> Result<()> {
+        if self.is_named() || self.sends_event() == send_event {
+            return Ok(());
+        }
+        Err(Error::MachineError(format!(
+            "{}Callback {} does not match send_event={}.",
+            machine_name, self, send_event
+        )))
+    }
+
     #[allow(dead_code)]
-    pub fn execute(&self, event_data: &EventData) {
-        (self.function)(event_data)
+    pub fn execute(&self, event_data: &EventData<'_, S, E>) -> Result<()> {
+        match &self.function {
+            TriggerCallable::Event(f) => f(event_data),
+            TriggerCallable::Args(f) => f(event_data.args()),
+            TriggerCallable::Named => self.resolve_callable(event_data)?.execute(event_data),
+        }
     }
 
     /// Converts a model's property name, method name or a path to a callable into a callable.
@@ -44,9 +145,17 @@ impl TriggerFunction {
     ///             event_data (EventData): Currently processed event
     ///         Returns:
     ///             callable function resolved from string or func
-    #[allow(dead_code)]
-    pub fn resolve_callable(self, _event_data: &EventData) -> Self {
-        todo!()
+    pub fn resolve_callable<'a>(&'a self, event_data: &'a EventData<'_, S, E>) -> Result<&'a Self> {
+        if !self.is_named() {
+            return Ok(self);
+        }
+        let name = self.name.as_deref().unwrap_or_default();
+        event_data
+            .machine
+            .registry()
+            .callback(name)
+            .filter(|func| !func.is_named())
+            .ok_or_else(|| Error::CallableError(name.to_string()))
     }
 
     // pub fn chain<U>(self, other: U) -> Chain<Self, <U as IntoIterator>::IntoIter>
@@ -54,38 +163,45 @@ impl TriggerFunction {
     //         U: IntoIterator<Item = Self::Item>, { self.}
 }
 
-impl Debug for TriggerFunction {
+impl<S, E> Debug for TriggerFunction<S, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(f, "TriggerFunction({:?})", self.name)
     }
 }
 
-impl Display for TriggerFunction {
+impl<S: StateValue, E: EventValue> From<&str> for TriggerFunction<S, E> {
+    fn from(name: &str) -> Self {
+        TriggerFunction::named(name)
+    }
+}
+
+impl<S, E> Display for TriggerFunction<S, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         Debug::fmt(self, f)
     }
 }
 
 #[derive(Debug, Display, EnumString, IntoStaticStr, EnumVariantNames)]
+#[strum(serialize_all = "snake_case")]
 pub enum StateTriggerType {
     Enter,
     Exit,
 }
 
 #[derive(Debug)]
-pub enum StateTrigger {
+pub enum StateTrigger<S = String, E = String> {
     #[allow(dead_code)]
-    EnterTrigger(TriggerFunction),
+    EnterTrigger(TriggerFunction<S, E>),
     #[allow(dead_code)]
-    ExitTrigger(TriggerFunction),
+    ExitTrigger(TriggerFunction<S, E>),
 }
 
-impl StateTrigger {
+impl<S: StateValue, E: EventValue> StateTrigger<S, E> {
     //         Must be one of                 'enter' or 'exit'.
     #[allow(dead_code)]
     pub fn from_func_and_type_name(
         trigger: StateTriggerType,
-        func: TriggerFunction,
+        func: TriggerFunction<S, E>,
     ) -> Result<Self> {
         match trigger {
             StateTriggerType::Enter => Ok(StateTrigger::EnterTrigger(func)),
@@ -94,7 +210,7 @@ impl StateTrigger {
     }
 
     #[allow(dead_code)]
-    pub fn execute(&self, event_data: &EventData) {
+    pub fn execute(&self, event_data: &EventData<'_, S, E>) -> Result<()> {
         match self {
             StateTrigger::EnterTrigger(e) => e.execute(event_data),
             StateTrigger::ExitTrigger(e) => e.execute(event_data),
@@ -102,7 +218,7 @@ impl StateTrigger {
     }
 
     #[allow(dead_code)]
-    pub fn callback(&self) -> &TriggerFunction {
+    pub fn callback(&self) -> &TriggerFunction<S, E> {
         match self {
             StateTrigger::EnterTrigger(e) => e,
             StateTrigger::ExitTrigger(e) => e,
@@ -110,6 +226,15 @@ impl StateTrigger {
     }
 }
 
+/// The values a ``Machine`` may use to identify its states. Besides ``String`` this is implemented
+///     for every enumeration that can be converted from and into a string, e.g. by deriving strum's
+///     ``AsRefStr`` and ``EnumString``. The string form is the name of the state which is assigned
+///     to the model(s). Transitions and state queries take values of this type, so that a
+///     ``Machine<Phase>`` rejects misspelled states at compile time.
+pub trait StateValue: Eq + Hash + Clone + Debug + AsRef<str> + FromStr + 'static {}
+
+impl<T: Eq + Hash + Clone + Debug + AsRef<str> + FromStr + 'static> StateValue for T {}
+
 /// A persistent representation of a state managed by a ``Machine``.
 ///     Attributes:
 ///         name (str): State name which is also assigned to the model(s).
@@ -117,14 +242,12 @@ impl StateTrigger {
 ///         on_exit (list): Callbacks executed when a state is exited.
 ///         ignore_invalid_triggers (bool): Indicates if unhandled/invalid triggers should raise an exception.
 #[derive(Debug)]
-pub struct State {
-    // # A list of dynamic methods which can be resolved by a ``Machine`` instance for convenience functions.
-    // # Dynamic methods for states must always start with `on_`!
-    // dynamic_methods = ['on_enter', 'on_exit']
+pub struct State<S = String, E = String> {
+    value: S,
     name: String,
-    ignore_invalid_triggers: bool,
-    on_enter: Vec<StateTrigger>,
-    on_exit: Vec<StateTrigger>,
+    pub(crate) ignore_invalid_triggers: Option<bool>,
+    on_enter: Vec<StateTrigger<S, E>>,
+    on_exit: Vec<StateTrigger<S, E>>,
 }
 
 ///         Args:
@@ -136,31 +259,29 @@ pub struct State {
 ///                 state is exited. Can be either a string providing the name of a
 ///                 callable, or a list of strings.
 ///             ignore_invalid_triggers (Boolean): Optional flag to indicate if
-///                 unhandled/invalid triggers should raise an exception
-impl State {
+///                 unhandled/invalid triggers should raise an exception. If not set,
+///                 the setting of the ``Machine`` is used.
+impl<S: StateValue, E: EventValue> State<S, E> {
+    /// A list of dynamic methods which can be resolved by a ``Machine`` instance for convenience functions.
+    ///     Dynamic methods for states must always start with `on_`!
+    pub(crate) const DYNAMIC_METHODS: &'static [&'static str] = &["on_enter", "on_exit"];
+
     #[allow(dead_code)]
     pub fn new(
-        name: String,
-        on_enter_fns: Option<Vec<TriggerFunction>>,
-        on_exit_fns: Option<Vec<TriggerFunction>>,
-        ignore_invalid_triggers: bool,
+        value: S,
+        on_enter_fns: Option<Vec<TriggerFunction<S, E>>>,
+        on_exit_fns: Option<Vec<TriggerFunction<S, E>>>,
+        ignore_invalid_triggers: Option<bool>,
     ) -> Self {
         let on_enter = on_enter_fns
-            .map(|f| {
-                f.into_iter()
-                    .map(|e| StateTrigger::EnterTrigger(e))
-                    .collect()
-            })
-            .unwrap_or_else(|| Vec::<StateTrigger>::new());
+            .map(|f| f.into_iter().map(StateTrigger::EnterTrigger).collect())
+            .unwrap_or_default();
         let on_exit = on_exit_fns
-            .map(|f| {
-                f.into_iter()
-                    .map(|e| StateTrigger::ExitTrigger(e))
-                    .collect()
-            })
-            .unwrap_or_else(|| Vec::<StateTrigger>::new());
+            .map(|f| f.into_iter().map(StateTrigger::ExitTrigger).collect())
+            .unwrap_or_default();
         State {
-            name,
+            name: value.as_ref().to_string(),
+            value,
             on_enter,
             on_exit,
             ignore_invalid_triggers,
@@ -168,18 +289,20 @@ impl State {
     }
 
     #[allow(dead_code)]
-    pub fn from_str(_name: &str) -> Self {
-        todo!()
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
+    /// The value of the state, e.g. an enumeration member. ``name`` is derived from it.
     #[allow(dead_code)]
-    pub fn name(self) -> String {
-        self.name.clone()
+    pub fn value(&self) -> &S {
+        &self.value
     }
 
-    #[allow(dead_code)]
-    pub fn value(&self) -> String {
-        self.name.clone()
+    /// Indicates if unhandled/invalid triggers should be silently ignored.
+    ///         Returns None if the state defers to the setting of the ``Machine``.
+    pub fn ignore_invalid_triggers(&self) -> Option<bool> {
+        self.ignore_invalid_triggers
     }
     // """ @property// def:// if isinstance(self._name, Enum): """    // def:%s%s/// _LOGGER.debug
     // return self._name.name// else:// return self._name// @property// def:// return self._name
@@ -187,48 +310,78 @@ impl State {
 
     /// Triggered when a state is entered.
     #[allow(dead_code)]
-    pub fn enter(&self, event_data: &EventData) {
+    pub fn enter(&self, event_data: &EventData<'_, S, E>) -> Result<()> {
         debug!(
             "{}: Entering state {}. Processing callbacks...",
             event_data.machine.name, self.name
         );
-        let machine = &*event_data.machine;
-        let mut callbacks: Vec<&TriggerFunction> = Vec::new();
+        let machine = event_data.machine;
+        let mut callbacks: Vec<&TriggerFunction<S, E>> = Vec::new();
         for func in self.on_enter.as_slice() {
             callbacks.push(func.callback());
         }
 
-        machine.callbacks(callbacks.as_slice(), event_data);
+        machine.callbacks(callbacks.as_slice(), event_data)?;
         info!(
             "{}: Finished processing state {} enter callbacks.",
             event_data.machine.name, self.name
         );
+        Ok(())
     }
 
     /// Triggered when a state is exited.
     #[allow(dead_code)]
-    pub fn exit(&self, event_data: &EventData) {
+    pub fn exit(&self, event_data: &EventData<'_, S, E>) -> Result<()> {
         debug!(
             "{}: Exiting state {}. Processing callbacks...",
             event_data.machine.name, self.name
         );
-        let machine = &*event_data.machine;
-        let mut callbacks: Vec<&TriggerFunction> = Vec::new();
+        let machine = event_data.machine;
+        let mut callbacks: Vec<&TriggerFunction<S, E>> = Vec::new();
         for func in self.on_exit.as_slice() {
             callbacks.push(func.callback());
         }
-        machine.callbacks(callbacks.as_slice(), event_data);
+        machine.callbacks(callbacks.as_slice(), event_data)?;
         info!(
             "{}: Finished processing state {} exit callbacks.",
             event_data.machine.name, self.name
         );
+        Ok(())
+    }
+
+    /// Indicates if a callback with the passed name is already part of the enter or exit callbacks.
+    pub(crate) fn has_callback(&self, name: &str) -> bool {
+        self.on_enter
+            .iter()
+            .chain(self.on_exit.iter())
+            .any(|trigger| trigger.callback().name() == Some(name))
+    }
+
+    /// Fail if any of the enter or exit callbacks does not match ``send_event``.
+    pub(crate) fn check_shape(&self, machine_name: &str, send_event: bool) -> Result<()> {
+        self.on_enter
+            .iter()
+            .chain(self.on_exit.iter())
+            .try_for_each(|trigger| trigger.callback().check_shape(machine_name, send_event))
+    }
+
+    /// The names of the enter or exit callbacks. None if a callback has no name.
+    pub(crate) fn callback_names(&self, trigger: StateTriggerType) -> Option<Vec<String>> {
+        let callbacks = match trigger {
+            StateTriggerType::Enter => &self.on_enter,
+            StateTriggerType::Exit => &self.on_exit,
+        };
+        callbacks
+            .iter()
+            .map(|trigger| trigger.callback().name().map(String::from))
+            .collect()
     }
 
     /// Add a new enter or exit callback.
     ///         Args:
     ///             trigger_func (str): The triggering event callback function.
     #[allow(dead_code)]
-    pub fn add_callback(&mut self, trigger_func: StateTrigger) {
+    pub fn add_callback(&mut self, trigger_func: StateTrigger<S, E>) {
         match trigger_func {
             StateTrigger::EnterTrigger(_) => self.on_enter.push(trigger_func),
             StateTrigger::ExitTrigger(_) => self.on_exit.push(trigger_func),
@@ -236,11 +389,11 @@ impl State {
     }
 }
 
-impl Display for State {
+impl<S: StateValue, E: EventValue> Display for State<S, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(
             f,
-            "State(name={}, ignore_invalid_triggers={})",
+            "State(name={}, ignore_invalid_triggers={:?})",
             self.name, self.ignore_invalid_triggers
         )
     }
@@ -248,23 +401,112 @@ impl Display for State {
     // return "<%s('%s')@%s>" % (type(self).__name__, self.name, id(self))
 }
 
-pub struct ConditionFunction {
-    function: Box<dyn Fn(&EventData) -> bool>,
+impl<E: EventValue> From<&str> for State<String, E> {
+    fn from(name: &str) -> Self {
+        State::new(name.to_string(), None, None, None)
+    }
+}
+
+impl<S: StateValue, E: EventValue> FromStr for State<S, E> {
+    type Err = Error;
+
+    /// Create a state without callbacks from its name.
+    ///         Raises UnknownStateError if the name cannot be converted into a state value.
+    fn from_str(name: &str) -> Result<Self> {
+        S::from_str(name)
+            .map(State::from)
+            .map_err(|_| Error::UnknownStateError(name.to_string()))
+    }
+}
+
+impl<S: StateValue, E: EventValue> From<S> for State<S, E> {
+    fn from(value: S) -> Self {
+        State::new(value, None, None, None)
+    }
+}
+
+/// See ``TriggerCallable``.
+#[derive(Clone)]
+#[allow(clippy::type_complexity)]
+enum ConditionCallable<S, E> {
+    Event(Rc<dyn Fn(&EventData<'_, S, E>) -> bool>),
+    Args(Rc<dyn Fn(&EventArgs) -> bool>),
+    Named,
+}
+
+#[derive(Clone)]
+pub struct ConditionFunction<S = String, E = String> {
+    function: ConditionCallable<S, E>,
     name: Option<String>,
 }
 
-impl ConditionFunction {
+impl<S: StateValue, E: EventValue> ConditionFunction<S, E> {
+    /// Create a condition which receives the ``EventData`` of the processed event.
     #[allow(dead_code)]
     pub fn new<F>(f: F, name: Option<String>) -> Self
     where
-        F: Fn(&EventData) -> bool + 'static,
+        F: Fn(&EventData<'_, S, E>) -> bool + 'static,
+    {
+        let function = ConditionCallable::Event(Rc::new(f));
+        ConditionFunction { function, name }
+    }
+
+    /// Create a condition which only receives the arguments passed to the trigger.
+    pub fn with_args<F>(f: F, name: Option<String>) -> Self
+    where
+        F: Fn(&EventArgs) -> bool + 'static,
     {
-        let function = Box::new(f);
+        let function = ConditionCallable::Args(Rc::new(f));
         ConditionFunction { function, name }
     }
 
-    pub fn execute(&self, event_data: &EventData) -> bool {
-        (self.function)(event_data)
+    /// Refer to a condition registered in the machine's ``CallbackRegistry``. The name is resolved
+    ///     when the event is processed.
+    pub fn named(name: &str) -> Self {
+        ConditionFunction {
+            function: ConditionCallable::Named,
+            name: Some(name.to_string()),
+        }
+    }
+
+    /// Indicates if the condition is only a reference to a condition in the ``CallbackRegistry``.
+    pub fn is_named(&self) -> bool {
+        matches!(self.function, ConditionCallable::Named)
+    }
+
+    pub(crate) fn or_named(mut self, name: &str) -> Self {
+        self.name.get_or_insert_with(|| name.to_string());
+        self
+    }
+
+    /// Indicates if the condition expects the ``EventData`` rather than the trigger arguments.
+    pub fn sends_event(&self) -> bool {
+        matches!(self.function, ConditionCallable::Event(_))
+    }
+
+    /// Fail if the condition does not match the machine's ``send_event``. Conditions referred to by
+    ///     name can only be checked once they have been resolved.
+    pub(crate) fn check_shape(&self, machine_name: &str, send_event: bool) -> Result<()> {
+        if self.is_named() || self.sends_event() == send_event {
+            return Ok(());
+        }
+        Err(Error::MachineError(format!(
+            "{}Condition {} does not match send_event={}.",
+            machine_name, self, send_event
+        )))
+    }
+
+    /// The name of the condition, if one has been given.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn execute(&self, event_data: &EventData<'_, S, E>) -> Result<bool> {
+        match &self.function {
+            ConditionCallable::Event(f) => Ok(f(event_data)),
+            ConditionCallable::Args(f) => Ok(f(event_data.args())),
+            ConditionCallable::Named => self.resolve_callable(event_data)?.execute(event_data),
+        }
     }
 
     /// Converts a model's property name, method name or a path to a callable into a callable.
@@ -274,54 +516,61 @@ impl ConditionFunction {
     ///             event_data (EventData): Currently processed event
     ///         Returns:
     ///             callable function resolved from string or func
-    pub fn resolve_callable(&self, _event_data: &EventData) -> Self {
-        // if isinstance(func, string_types):
-        // try:
-        // func = getattr(event_data.model, func)
-        // if not callable(func):  # if a property or some other not callable attribute was passed
-        // def func_wrapper(*_, **__):  # properties cannot process parameters
-        // return func
-        // return func_wrapper
-        // except AttributeError:
-        // try:
-        // mod, name = func.rsplit('.', 1)
-        // m = __import__(mod)
-        // for n in mod.split('.')[1:]:
-        // m = getattr(m, n)
-        // func = getattr(m, name)
-        // except (ImportError, AttributeError, ValueError):
-        // raise AttributeError("Callable with name '%s' could neither be retrieved from the passed "
-        // "model nor imported from a module." % func)
-        // return func
-        todo!()
-    }
-}
-
-impl Debug for ConditionFunction {
+    pub fn resolve_callable<'a>(&'a self, event_data: &'a EventData<'_, S, E>) -> Result<&'a Self> {
+        if !self.is_named() {
+            return Ok(self);
+        }
+        let name = self.name.as_deref().unwrap_or_default();
+        event_data
+            .machine
+            .registry()
+            .condition(name)
+            .filter(|func| !func.is_named())
+            .ok_or_else(|| Error::CallableError(name.to_string()))
+    }
+}
+
+impl<S, E> Debug for ConditionFunction<S, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(f, "ConditionFunction({:?})", self.name)
     }
 }
 
-impl Display for ConditionFunction {
+impl<S: StateValue, E: EventValue> From<&str> for ConditionFunction<S, E> {
+    fn from(name: &str) -> Self {
+        ConditionFunction::named(name)
+    }
+}
+
+impl<S, E> Display for ConditionFunction<S, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         Debug::fmt(self, f)
     }
 }
 
+/// What a ``Condition`` evaluates: a single condition-checking callable or a group of conditions.
+#[derive(Debug, Clone)]
+enum ConditionExpr<S, E> {
+    Func(ConditionFunction<S, E>),
+    All(Vec<Condition<S, E>>),
+    Any(Vec<Condition<S, E>>),
+}
+
 /// A helper class to call condition checks in the intended way.
+///     Conditions can be combined with ``Condition::all``, ``Condition::any`` and ``Condition::not``
+///     and nested arbitrarily, e.g. ``Condition::any(vec![Condition::not(is_locked), is_admin.into()])``.
 ///     Attributes:
-///         func (callable): The function to call for the condition check
+///         func (callable): The function or group of conditions to call for the condition check
 ///         target (bool): Indicates the target state--i.e., when True,
 ///                 the condition-checking callback should return True to pass,
 ///                 and when False, the callback should return False to pass.
-#[derive(Debug)]
-pub struct Condition {
-    func: ConditionFunction,
+#[derive(Debug, Clone)]
+pub struct Condition<S = String, E = String> {
+    func: ConditionExpr<S, E>,
     target: bool,
 }
 
-impl Condition {
+impl<S: StateValue, E: EventValue> Condition<S, E> {
     ///         Args:
     ///             func (str): Name of the condition-checking callable
     ///             target (bool): Indicates the target state--i.e., when True,
@@ -332,9 +581,79 @@ impl Condition {
     ///             Transition instance, and exists at module level (rather than
     ///             nesting under the transition class) only because of a bug in
     ///             dill that prevents serialization under Python 2.7.
-    #[allow(dead_code)]
-    fn new(func: ConditionFunction, target: bool) -> Self {
-        Condition { func, target }
+    fn new(func: ConditionFunction<S, E>, target: bool) -> Self {
+        Condition {
+            func: ConditionExpr::Func(func),
+            target,
+        }
+    }
+
+    /// A condition which passes if ALL passed conditions pass. An empty group always passes.
+    pub fn all<C, I>(conditions: I) -> Self
+    where
+        C: Into<Condition<S, E>>,
+        I: IntoIterator<Item = C>,
+    {
+        let conditions = conditions.into_iter().map(Into::into).collect();
+        Condition {
+            func: ConditionExpr::All(conditions),
+            target: true,
+        }
+    }
+
+    /// A condition which passes if ANY of the passed conditions passes. An empty group never passes.
+    pub fn any<C, I>(conditions: I) -> Self
+    where
+        C: Into<Condition<S, E>>,
+        I: IntoIterator<Item = C>,
+    {
+        let conditions = conditions.into_iter().map(Into::into).collect();
+        Condition {
+            func: ConditionExpr::Any(conditions),
+            target: true,
+        }
+    }
+
+    /// A condition which passes if the passed condition does not pass.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not<C: Into<Condition<S, E>>>(condition: C) -> Self {
+        let condition = condition.into();
+        Condition {
+            target: !condition.target,
+            ..condition
+        }
+    }
+
+    /// A readable name of the condition used for logging, e.g. ``any(is_admin, is_owner)``.
+    pub fn name(&self) -> String {
+        let group = |conditions: &[Condition<S, E>]| {
+            conditions
+                .iter()
+                .map(|cond| {
+                    if cond.target {
+                        cond.name()
+                    } else {
+                        format!("not({})", cond.name())
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match &self.func {
+            ConditionExpr::Func(func) => func.name().unwrap_or("<lambda>").to_string(),
+            ConditionExpr::All(conditions) => format!("all({})", group(conditions)),
+            ConditionExpr::Any(conditions) => format!("any({})", group(conditions)),
+        }
+    }
+
+    /// Fail if any of the grouped condition-checking callables does not match ``send_event``.
+    pub(crate) fn check_shape(&self, machine_name: &str, send_event: bool) -> Result<()> {
+        match &self.func {
+            ConditionExpr::Func(func) => func.check_shape(machine_name, send_event),
+            ConditionExpr::All(conditions) | ConditionExpr::Any(conditions) => conditions
+                .iter()
+                .try_for_each(|cond| cond.check_shape(machine_name, send_event)),
+        }
     }
 
     ///Check whether the condition passes.
@@ -344,50 +663,292 @@ impl Condition {
     ///                 from (if event sending is disabled). Also contains the data
     ///                 model attached to the current machine which is used to invoke
     ///                 the condition.
-    #[allow(dead_code)]
-    pub fn check(&mut self, event_data: &EventData) -> bool {
-        let predicate = &self.func.resolve_callable(event_data);
-        if event_data.machine.send_event {
-            return predicate.execute(event_data) == self.target;
-        }
-        todo!() // return predicate(*event_data.args, **event_data.kwargs) == self.target
+    pub fn check(&self, event_data: &EventData<'_, S, E>) -> Result<bool> {
+        let result = match &self.func {
+            ConditionExpr::Func(func) => {
+                let predicate = func.resolve_callable(event_data)?;
+                if func.is_named() {
+                    let machine = event_data.machine;
+                    predicate.check_shape(&machine.name, machine.send_event)?;
+                }
+                predicate.execute(event_data)?
+            }
+            ConditionExpr::All(conditions) => {
+                let mut result = true;
+                for cond in conditions {
+                    if !cond.check(event_data)? {
+                        result = false;
+                        break;
+                    }
+                }
+                result
+            }
+            ConditionExpr::Any(conditions) => {
+                let mut result = false;
+                for cond in conditions {
+                    if cond.check(event_data)? {
+                        result = true;
+                        break;
+                    }
+                }
+                result
+            }
+        };
+        Ok(result == self.target)
+    }
+}
+
+impl<S: StateValue, E: EventValue> From<ConditionFunction<S, E>> for Condition<S, E> {
+    fn from(func: ConditionFunction<S, E>) -> Self {
+        Condition::new(func, true)
     }
 }
 
-impl Display for Condition {
+impl<S: StateValue, E: EventValue> Display for Condition<S, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         //"<%s(%s)@%s>" % (type(self).__name__, self.func, id(self))
         // Can't `Debug::fmt(self, f)` because type "F" isn't `Debug`
-        write!(f, "Condition(func={},target={})", self.func, self.target)
+        write!(f, "Condition(func={},target={})", self.name(), self.target)
     }
 }
 
-#[derive(Debug, Display, EnumString, IntoStaticStr, EnumVariantNames)]
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr, EnumVariantNames)]
+#[strum(serialize_all = "snake_case")]
 pub enum TransitionTriggerType {
     Before,
     After,
     Prepare,
 }
 
-#[derive(Debug)]
-pub struct PotentialConditions {
-    conditions: Vec<Condition>,
+#[derive(Debug, Clone)]
+pub struct PotentialConditions<S = String, E = String> {
+    conditions: Vec<Condition<S, E>>,
 }
 
-#[derive(Debug)]
-pub struct PotentialTriggers {
-    triggers: Vec<TriggerFunction>,
+impl<S, E> Default for PotentialConditions<S, E> {
+    fn default() -> Self {
+        PotentialConditions {
+            conditions: Vec::new(),
+        }
+    }
+}
+
+impl<S: StateValue, E: EventValue> From<Vec<ConditionFunction<S, E>>>
+    for PotentialConditions<S, E>
+{
+    fn from(funcs: Vec<ConditionFunction<S, E>>) -> Self {
+        let conditions = funcs
+            .into_iter()
+            .map(|func| Condition::new(func, true))
+            .collect();
+        PotentialConditions { conditions }
+    }
+}
+
+impl<S: StateValue, E: EventValue> From<ConditionFunction<S, E>> for PotentialConditions<S, E> {
+    fn from(func: ConditionFunction<S, E>) -> Self {
+        PotentialConditions::from(vec![func])
+    }
+}
+
+impl<S: StateValue, E: EventValue> From<&str> for PotentialConditions<S, E> {
+    fn from(name: &str) -> Self {
+        PotentialConditions::from(ConditionFunction::named(name))
+    }
+}
+
+impl<S: StateValue, E: EventValue> From<Vec<Condition<S, E>>> for PotentialConditions<S, E> {
+    fn from(conditions: Vec<Condition<S, E>>) -> Self {
+        PotentialConditions { conditions }
+    }
 }
 
-impl Append<TriggerFunction> for PotentialTriggers {
-    type Common = TriggerFunction;
+impl<S: StateValue, E: EventValue> From<Condition<S, E>> for PotentialConditions<S, E> {
+    fn from(condition: Condition<S, E>) -> Self {
+        PotentialConditions::from(vec![condition])
+    }
+}
+
+impl<S: StateValue, E: EventValue> PotentialConditions<S, E> {
+    /// Invert the target of all conditions, e.g. to evaluate them as ``unless`` conditions.
+    fn inverted(self) -> Self {
+        let conditions = self.conditions.into_iter().map(Condition::not).collect();
+        PotentialConditions { conditions }
+    }
 
-    fn append(&mut self, value: TriggerFunction) {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    pub(crate) fn check_shape(&self, machine_name: &str, send_event: bool) -> Result<()> {
+        self.conditions
+            .iter()
+            .try_for_each(|cond| cond.check_shape(machine_name, send_event))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PotentialTriggers<S = String, E = String> {
+    triggers: Vec<TriggerFunction<S, E>>,
+}
+
+impl<S, E> Default for PotentialTriggers<S, E> {
+    fn default() -> Self {
+        PotentialTriggers {
+            triggers: Vec::new(),
+        }
+    }
+}
+
+impl<S, E> PotentialTriggers<S, E> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.triggers.is_empty()
+    }
+}
+
+impl<S: StateValue, E: EventValue> PotentialTriggers<S, E> {
+    pub(crate) fn check_shape(&self, machine_name: &str, send_event: bool) -> Result<()> {
+        self.triggers
+            .iter()
+            .try_for_each(|func| func.check_shape(machine_name, send_event))
+    }
+}
+
+impl<S: StateValue, E: EventValue> From<Vec<TriggerFunction<S, E>>> for PotentialTriggers<S, E> {
+    fn from(triggers: Vec<TriggerFunction<S, E>>) -> Self {
+        PotentialTriggers { triggers }
+    }
+}
+
+impl<S: StateValue, E: EventValue> From<TriggerFunction<S, E>> for PotentialTriggers<S, E> {
+    fn from(func: TriggerFunction<S, E>) -> Self {
+        PotentialTriggers::from(vec![func])
+    }
+}
+
+impl<S: StateValue, E: EventValue> From<&str> for PotentialTriggers<S, E> {
+    fn from(name: &str) -> Self {
+        PotentialTriggers::from(TriggerFunction::named(name))
+    }
+}
+
+impl<S: StateValue, E: EventValue> Append<TriggerFunction<S, E>> for PotentialTriggers<S, E> {
+    type Common = TriggerFunction<S, E>;
+
+    fn append(&mut self, value: TriggerFunction<S, E>) {
         self.triggers.push(value)
     }
 }
 
-pub struct TransitionParameters;
+/// The arguments of ``Machine::add_transition`` bundled up, so that transitions can be passed
+///     to ``Machine::new`` and ``Machine::add_transitions`` as a list.
+///     Attributes:
+///         trigger (str): The name of the event that will trigger the transition.
+///         source (list): The name(s) of the source state(s) or '*' for all states.
+///         dest (str): The name of the destination state, '=' for reflexive transitions and None for
+///             internal transitions.
+///         conditions, unless, before, after, prepare: See ``Transition``.
+#[derive(Debug, Clone)]
+pub struct TransitionParameters<S = String, E = String> {
+    pub trigger: String,
+    pub source: Vec<String>,
+    pub dest: Option<String>,
+    pub conditions: PotentialConditions<S, E>,
+    pub unless: PotentialConditions<S, E>,
+    pub before: PotentialTriggers<S, E>,
+    pub after: PotentialTriggers<S, E>,
+    pub prepare: PotentialTriggers<S, E>,
+}
+
+impl<S: StateValue, E: EventValue> TransitionParameters<S, E> {
+    ///         Args:
+    ///             trigger (str or EventValue): The event that will trigger the transition.
+    ///             source (list): The source state(s).
+    ///             dest (str or Enum): The destination state.
+    pub fn new<K: Into<E>, T: Clone + Into<S>>(trigger: K, source: &[T], dest: T) -> Self {
+        Self::from_values(trigger.into(), source, Some(dest.into()))
+    }
+
+    /// Parameters of an internal transition. Its before and after callbacks are processed but the
+    ///     state is neither exited nor entered.
+    ///         Args:
+    ///             trigger (str or EventValue): The event that will trigger the transition.
+    ///             source (list): The source state(s). Can be strings or enumeration members.
+    pub fn internal<K: Into<E>, T: Clone + Into<S>>(trigger: K, source: &[T]) -> Self {
+        Self::from_values(trigger.into(), source, None)
+    }
+
+    /// Parameters of a transition from all states, including states added later (source '*').
+    ///         Args:
+    ///             trigger (str or EventValue): The event that will trigger the transition.
+    ///             dest (str or Enum): The destination state.
+    pub fn wildcard<K: Into<E>, T: Into<S>>(trigger: K, dest: T) -> Self {
+        let (trigger, dest) = (trigger.into(), dest.into());
+        let wildcard = WILDCARD_ALL.to_string();
+        Self::from_names(trigger.as_ref(), &[wildcard.as_str()], Some(dest.as_ref()))
+    }
+
+    /// Parameters of a reflexive transition whose destination is its source (dest '=').
+    ///         Args:
+    ///             trigger (str or EventValue): The event that will trigger the transition.
+    ///             source (list): The source state(s).
+    pub fn reflexive<K: Into<E>, T: Clone + Into<S>>(trigger: K, source: &[T]) -> Self {
+        let mut params = Self::from_values(trigger.into(), source, None);
+        params.dest = Some(WILDCARD_SAME.to_string());
+        params
+    }
+
+    fn from_values<T: Clone + Into<S>>(trigger: E, source: &[T], dest: Option<S>) -> Self {
+        let source: Vec<S> = source.iter().cloned().map(Into::into).collect();
+        let source: Vec<&str> = source.iter().map(|s| s.as_ref()).collect();
+        Self::from_names(trigger.as_ref(), &source, dest.as_ref().map(|d| d.as_ref()))
+    }
+
+    /// Parameters referring to the event and states by name, as done by machine definitions.
+    ///     Names are checked when the transition is added to a machine.
+    pub(crate) fn from_names(trigger: &str, source: &[&str], dest: Option<&str>) -> Self {
+        TransitionParameters {
+            trigger: trigger.to_string(),
+            source: source.iter().map(|s| s.to_string()).collect(),
+            dest: dest.map(|d| d.to_string()),
+            conditions: PotentialConditions::default(),
+            unless: PotentialConditions::default(),
+            before: PotentialTriggers::default(),
+            after: PotentialTriggers::default(),
+            prepare: PotentialTriggers::default(),
+        }
+    }
+
+    /// Condition(s) that must pass in order for the transition to take place.
+    pub fn conditions<C: Into<PotentialConditions<S, E>>>(mut self, conditions: C) -> Self {
+        self.conditions = conditions.into();
+        self
+    }
+
+    /// Condition(s) that must not pass in order for the transition to take place.
+    pub fn unless<C: Into<PotentialConditions<S, E>>>(mut self, unless: C) -> Self {
+        self.unless = unless.into();
+        self
+    }
+
+    /// Callbacks to trigger before the transition.
+    pub fn before<T: Into<PotentialTriggers<S, E>>>(mut self, before: T) -> Self {
+        self.before = before.into();
+        self
+    }
+
+    /// Callbacks to trigger after the transition.
+    pub fn after<T: Into<PotentialTriggers<S, E>>>(mut self, after: T) -> Self {
+        self.after = after.into();
+        self
+    }
+
+    /// Callbacks to trigger before conditions are checked.
+    pub fn prepare<T: Into<PotentialTriggers<S, E>>>(mut self, prepare: T) -> Self {
+        self.prepare = prepare.into();
+        self
+    }
+}
 
 /// Representation of a transition managed by a ``Machine`` instance.
 ///     Attributes:
@@ -395,31 +956,31 @@ pub struct TransitionParameters;
 ///         dest (str): Destination state of the transition.
 ///         prepare (list): Callbacks executed before conditions checks.
 ///         conditions (list): Callbacks evaluated to determine if
-///             the transition should be executed.
+///             the transition should be executed. This includes the inverted ``unless`` conditions.
 ///         before (list): Callbacks executed before the transition is executed
 ///             but only if condition checks have been successful.
 ///         after (list): Callbacks executed after the transition is executed
 ///             but only if condition checks have been successful.
-#[derive(Debug)]
-pub struct Transition {
+#[derive(Debug, Clone)]
+pub struct Transition<S = String, E = String> {
     source: String,
     dest: Option<String>,
-    conditions: PotentialConditions,
-    unless: PotentialConditions,
-    before: PotentialTriggers,
-    after: PotentialTriggers,
-    prepare: PotentialTriggers,
+    conditions: PotentialConditions<S, E>,
+    before: PotentialTriggers<S, E>,
+    after: PotentialTriggers<S, E>,
+    prepare: PotentialTriggers<S, E>,
+    // whether the transition is equivalent to the auto transition 'to_<dest>' and is re-created by
+    // it when a definition is loaded, e.g. a user transition without callbacks or conditions
+    pub(crate) auto: bool,
 }
 
-///  A list of dynamic methods which can be resolved by a ``Machine`` instance for convenience functions.&''''''""""""
-#[allow(dead_code)]
-const DYNAMIC_METHODS: &[&'static str] = TransitionTriggerType::VARIANTS;
+//  Conditions are combined with AND ('conditions') and OR ('unless'). Use ``Condition::any`` or
+//          ``Condition::all`` to alter this behaviour instead of replacing the condition class.
 
-//  The class used to wrap condition checks. Can be replaced to alter condition resolution behaviour
-//          (e.g. OR instead of AND for 'conditions' or AND instead of OR for 'unless')
-// condition_cls = Condition
+impl<S: StateValue, E: EventValue> Transition<S, E> {
+    /// A list of dynamic methods which can be resolved by a ``Machine`` instance for convenience functions.
+    pub(crate) const DYNAMIC_METHODS: &'static [&'static str] = TransitionTriggerType::VARIANTS;
 
-impl Transition {
     ///         Args:
     ///             source (str): The name of the source State.
     ///             dest (str): The name of the destination State.
@@ -438,113 +999,155 @@ impl Transition {
     pub fn new(
         source: String,
         dest: Option<String>,
-        conditions: PotentialConditions,
-        unless: PotentialConditions,
-        before: PotentialTriggers,
-        after: PotentialTriggers,
-        prepare: PotentialTriggers,
+        conditions: PotentialConditions<S, E>,
+        unless: PotentialConditions<S, E>,
+        before: PotentialTriggers<S, E>,
+        after: PotentialTriggers<S, E>,
+        prepare: PotentialTriggers<S, E>,
     ) -> Self {
+        let mut conditions = conditions;
+        conditions.conditions.extend(unless.inverted().conditions);
         Transition {
             source,
             dest,
             conditions,
-            unless,
             before,
             after,
             prepare,
+            auto: false,
         }
     }
 
-    #[allow(dead_code)]
-    fn eval_conditions(&mut self, event_data: &EventData) -> bool {
-        for
-        /*mut*/
-        cond in self.conditions.conditions.iter_mut() {
-            if !cond.check(&event_data) {
+    /// The name of the source State.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The name of the destination State. None for internal transitions.
+    pub fn dest(&self) -> Option<&str> {
+        self.dest.as_deref()
+    }
+
+    /// Indicates if a callback with the passed name is already part of the before, after or prepare callbacks.
+    pub(crate) fn has_callback(&self, name: &str) -> bool {
+        self.before
+            .triggers
+            .iter()
+            .chain(self.after.triggers.iter())
+            .chain(self.prepare.triggers.iter())
+            .any(|func| func.name() == Some(name))
+    }
+
+    /// The names of the before, after or prepare callbacks. None if a callback has no name.
+    pub(crate) fn callback_names(&self, trigger: TransitionTriggerType) -> Option<Vec<String>> {
+        let callbacks = match trigger {
+            TransitionTriggerType::Before => &self.before,
+            TransitionTriggerType::After => &self.after,
+            TransitionTriggerType::Prepare => &self.prepare,
+        };
+        callbacks
+            .triggers
+            .iter()
+            .map(|func| func.name().map(String::from))
+            .collect()
+    }
+
+    /// The names of the conditions which must return ``target``, i.e. ``conditions`` for True and
+    ///     ``unless`` for False. None if a condition has no name or combines conditions with
+    ///     ``Condition::any`` or ``Condition::all``.
+    pub(crate) fn condition_names(&self, target: bool) -> Option<Vec<String>> {
+        self.conditions
+            .conditions
+            .iter()
+            .filter(|cond| cond.target == target)
+            .map(|cond| match &cond.func {
+                ConditionExpr::Func(func) => func.name().map(String::from),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Check whether the transition could be executed without executing it. Prepare callbacks are
+    ///     processed and conditions are evaluated but the state remains unchanged.
+    ///         Args:
+    ///             event_data: An instance of class EventData.
+    ///         Returns: boolean indicating whether or not all conditions pass.
+    pub(crate) fn may_execute(&self, event_data: &EventData<'_, S, E>) -> Result<bool> {
+        let machine = event_data.machine;
+        machine.callbacks(
+            self.prepare.triggers.iter().collect::<Vec<_>>().as_slice(),
+            event_data,
+        )?;
+        self.eval_conditions(event_data)
+    }
+
+    fn eval_conditions(&self, event_data: &EventData<'_, S, E>) -> Result<bool> {
+        for cond in self.conditions.conditions.iter() {
+            if !cond.check(event_data)? {
                 debug!(
                     "{} Transition condition failed: {}() does not return {}. Transition halted.",
-                    event_data.machine.name, "cond.func", &cond.target
+                    event_data.machine.name,
+                    cond.name(),
+                    &cond.target
                 );
-                return false;
+                return Ok(false);
             }
         }
-        return true;
+        Ok(true)
     }
 
     // Execute the transition.
     ///         Args:
     ///             event_data: An instance of class EventData.
     ///         Returns: boolean indicating whether or not the transition was
-    ///             successfully executed (True if successful, False if not).&iter().map(|f| f).collect()
+    ///             successfully executed (True if successful, False if not).
     #[allow(dead_code)]
-    pub fn execute(&mut self, event_data: &mut EventData) -> bool {
+    pub fn execute<'a>(&'a self, event_data: &mut EventData<'a, S, E>) -> Result<bool> {
         debug!(
             "{}: Initiating transition from state {} to state ...{:?}",
             event_data.machine.name, self.source, self.dest
         );
-        event_data.machine.callbacks(
-            &self.prepare.triggers.iter().collect::<Vec<_>>().as_slice(),
-            &event_data,
-        );
-        debug!(
-            "{}: Executed callbacks before conditions.",
-            &event_data.machine.name,
-        );
-        if !self.eval_conditions(&event_data) {
-            return false;
+        let machine = event_data.machine;
+        machine.callbacks(
+            self.prepare.triggers.iter().collect::<Vec<_>>().as_slice(),
+            event_data,
+        )?;
+        debug!("{}: Executed callbacks before conditions.", machine.name);
+        if !self.eval_conditions(event_data)? {
+            return Ok(false);
         }
 
-        let functions: Vec<_> = event_data
-            .machine
+        let functions: Vec<_> = machine
             .before_state_change
             .iter()
             .chain(&self.before.triggers)
-            .into_iter()
             .collect();
-        event_data
-            .machine
-            .callbacks(functions.as_slice(), &event_data);
-        debug!(
-            "{}: Executed callback before transition.",
-            event_data.machine.name
-        );
+        machine.callbacks(functions.as_slice(), event_data)?;
+        debug!("{}: Executed callback before transition.", machine.name);
 
         // if self.dest is None this is an internal transition with no actual state change
         if self.dest.is_some() {
-            self.change_state(event_data)
+            self.change_state(event_data)?;
         }
         let functions: Vec<_> = self
             .after
             .triggers
             .iter()
-            .chain(&event_data.machine.after_state_change)
-            .into_iter()
+            .chain(&machine.after_state_change)
             .collect();
-        event_data
-            .machine
-            .callbacks(functions.as_slice(), &event_data);
-        debug!(
-            "{}: Executed callback after transition.",
-            event_data.machine.name
-        );
-        return true;
+        machine.callbacks(functions.as_slice(), event_data)?;
+        debug!("{}: Executed callback after transition.", machine.name);
+        Ok(true)
     }
 
     #[allow(dead_code)]
-    fn change_state(&mut self, event_data: &mut EventData) {
-        //lf.
-        let dest_state =
-            State::from_str(self.dest.as_ref().map(|s| s.borrow()).unwrap_or_else(|| ""));
-        let source_state = State::from_str(&self.source);
-        event_data.machine.get_state(&source_state).exit(event_data);
-        event_data
-            .machine
-            .set_state(&dest_state, Some(event_data.model));
-        event_data.update(getattr(
-            event_data.model,
-            &*event_data.machine.model_attribute,
-        ));
-        event_data.machine.get_state(&dest_state).enter(event_data)
+    fn change_state<'a>(&'a self, event_data: &mut EventData<'a, S, E>) -> Result<()> {
+        let machine = event_data.machine;
+        let dest = self.dest.as_deref().unwrap_or("");
+        machine._get_state(&self.source)?.exit(event_data)?;
+        machine._set_state(dest, Some(event_data.model))?;
+        event_data.update(machine.get_model_state(event_data.model)?);
+        machine._get_state(dest)?.enter(event_data)
     }
 
     /// Add a new before, after, or prepare callback.
@@ -553,7 +1156,7 @@ impl Transition {
     ///                 'before', 'after' or 'prepare'.
     ///             func (str): The name of the callback function.
     #[allow(dead_code)]
-    pub fn add_callback(&mut self, trigger: TransitionTriggerType, func: TriggerFunction) {
+    pub fn add_callback(&mut self, trigger: TransitionTriggerType, func: TriggerFunction<S, E>) {
         // callback_list = getattr(self, trigger)
         match trigger {
             TransitionTriggerType::Before => self.before.append(func),
@@ -563,7 +1166,7 @@ impl Transition {
     }
 }
 
-impl Display for Transition {
+impl<S: StateValue, E: EventValue> Display for Transition<S, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(
             f,
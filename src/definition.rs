@@ -0,0 +1,676 @@
+//! Machine definitions in the format of pytransitions' dict based configuration. This allows machines
+//! to be defined by non-Rust users, e.g. in JSON or YAML, and definitions to be shared with PyTransitions.
+//!
+//! Callbacks and conditions are referred to by name and resolved through the ``CallbackRegistry``
+//! passed along with the definition. A ``Machine`` can be exported into a definition again, which
+//! produces the same shape as pytransitions' ``MarkupMachine``.
+
+use crate::core::{
+    ConditionFunction, PotentialConditions, PotentialTriggers, State, StateTrigger,
+    StateTriggerType, StateValue, TransitionParameters, TransitionTriggerType, TriggerFunction,
+};
+use crate::error::Error;
+use crate::event::EventValue;
+use crate::machine::Machine;
+use crate::registry::CallbackRegistry;
+use crate::support::listify;
+use crate::Result;
+use serde::de::{self, value::MapAccessDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Formatter, Result as FmtResult};
+
+/// The definition of a state.
+///     Attributes:
+///         name (str): The name of the state.
+///         on_enter (list): Names of the callbacks executed when the state is entered.
+///         on_exit (list): Names of the callbacks executed when the state is exited.
+///         ignore_invalid_triggers (bool): Overrides the setting of the machine if set.
+///     Unknown keys are rejected.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateDefinition {
+    pub name: String,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub on_enter: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub on_exit: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_invalid_triggers: Option<bool>,
+}
+
+/// The definition of a transition. In a definition it can either be written as a dict with the
+///     keys below or as a list in the order of the arguments of ``Machine.add_transition``,
+///     e.g. ``["advance", "A", "B"]``.
+///     Attributes:
+///         trigger (str): The name of the triggering event.
+///         source (list): The name(s) of the source state(s). '*' refers to all states.
+///         dest (str): The name of the destination state and '=' for reflexive transitions. The key
+///             is required; only an explicit null (None) defines an internal transition.
+///         conditions, unless (list): Names of the conditions.
+///         before, after, prepare (list): Names of the callbacks.
+///     Unknown keys are rejected.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransitionDefinition {
+    pub trigger: String,
+    #[serde(deserialize_with = "listify", serialize_with = "serialize_source")]
+    pub source: Vec<String>,
+    // a missing key must not silently turn into an internal transition
+    #[serde(deserialize_with = "Option::deserialize")]
+    pub dest: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub conditions: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub unless: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub before: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub after: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub prepare: Vec<String>,
+}
+
+/// The definition of a whole machine. Keys and defaults follow the arguments of ``Machine.__init__``
+///     in pytransitions; unknown keys such as ``models`` are ignored.
+///     Attributes:
+///         name (str): The name of the machine.
+///         states (list): States as names or dicts.
+///         transitions (list): Transitions as lists or dicts.
+///         initial (str): The initial state. Defaults to 'initial' which is added if necessary.
+///         auto_transitions, ordered_transitions, send_event, queued (bool): See ``Machine::new``.
+///         ignore_invalid_triggers (bool or None): See ``Machine::new``. ``MarkupMachine`` writes None
+///             for its default, which is treated as False.
+///         model_attribute (str): The attribute the state is stored in. Defaults to 'state'.
+///         before_state_change, after_state_change, prepare_event, finalize_event, on_exception (list):
+///             Names of the machine callbacks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MachineDefinition {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_states")]
+    pub states: Vec<StateDefinition>,
+    #[serde(default, deserialize_with = "deserialize_transitions")]
+    pub transitions: Vec<TransitionDefinition>,
+    #[serde(default = "default_initial")]
+    pub initial: Option<String>,
+    #[serde(default = "default_true")]
+    pub auto_transitions: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub ordered_transitions: bool,
+    #[serde(default)]
+    pub send_event: bool,
+    #[serde(default)]
+    pub ignore_invalid_triggers: Option<bool>,
+    #[serde(default)]
+    pub queued: bool,
+    #[serde(default)]
+    pub model_attribute: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub before_state_change: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub after_state_change: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub prepare_event: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub finalize_event: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "listify",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub on_exception: Vec<String>,
+}
+
+fn default_initial() -> Option<String> {
+    Some(String::from("initial"))
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Transitions with a single source are written with the source as string like ``MarkupMachine`` does.
+fn serialize_source<Ser: Serializer>(
+    source: &[String],
+    serializer: Ser,
+) -> std::result::Result<Ser::Ok, Ser::Error> {
+    match source {
+        [source] => serializer.serialize_str(source),
+        sources => sources.serialize(serializer),
+    }
+}
+
+/// A list of names which may also be passed as a single name.
+#[derive(Default, Deserialize)]
+struct Names(#[serde(deserialize_with = "listify")] Vec<String>);
+
+/// A state passed either by name or as dict.
+struct AnyState(StateDefinition);
+
+impl<'de> Deserialize<'de> for AnyState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct StateVisitor;
+
+        impl<'de> Visitor<'de> for StateVisitor {
+            type Value = AnyState;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> FmtResult {
+                f.write_str("a state name or a dict with the key 'name'")
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> std::result::Result<Self::Value, E> {
+                Ok(AnyState(StateDefinition {
+                    name: name.to_string(),
+                    ..StateDefinition::default()
+                }))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                StateDefinition::deserialize(MapAccessDeserializer::new(map)).map(AnyState)
+            }
+        }
+
+        deserializer.deserialize_any(StateVisitor)
+    }
+}
+
+/// A transition passed either as list or as dict.
+struct AnyTransition(TransitionDefinition);
+
+impl<'de> Deserialize<'de> for AnyTransition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct TransitionVisitor;
+
+        impl<'de> Visitor<'de> for TransitionVisitor {
+            type Value = AnyTransition;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> FmtResult {
+                f.write_str("a list [trigger, source, dest, ...] or a dict with the keys 'trigger', 'source' and 'dest'")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                let trigger: String = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let source: Names = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                let dest: Option<String> = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+                let mut optional = || -> std::result::Result<Vec<String>, A::Error> {
+                    Ok(seq.next_element::<Names>()?.unwrap_or_default().0)
+                };
+                Ok(AnyTransition(TransitionDefinition {
+                    trigger,
+                    source: source.0,
+                    dest,
+                    conditions: optional()?,
+                    unless: optional()?,
+                    before: optional()?,
+                    after: optional()?,
+                    prepare: optional()?,
+                }))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                TransitionDefinition::deserialize(MapAccessDeserializer::new(map))
+                    .map(AnyTransition)
+            }
+        }
+
+        deserializer.deserialize_any(TransitionVisitor)
+    }
+}
+
+/// Deserialize the states of a machine. ``null`` results in an empty list.
+fn deserialize_states<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<StateDefinition>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let states: Option<Vec<AnyState>> = Deserialize::deserialize(deserializer)?;
+    Ok(states
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| s.0)
+        .collect())
+}
+
+/// Deserialize the transitions of a machine. ``null`` results in an empty list.
+fn deserialize_transitions<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<TransitionDefinition>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let transitions: Option<Vec<AnyTransition>> = Deserialize::deserialize(deserializer)?;
+    Ok(transitions
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| t.0)
+        .collect())
+}
+
+/// Convert a parser message into a ``DefinitionError``. Parsers append the position to their
+///     messages which is removed since it is part of the error already. Errors without a position,
+///     such as an unexpected end of the document, are reported at the end of the document.
+pub(crate) fn definition_error(
+    message: String,
+    location: Option<(usize, usize)>,
+    document: &str,
+) -> Error {
+    let (line, column) = location
+        .filter(|(line, _)| *line > 0)
+        .unwrap_or_else(|| end_of_document(document));
+    let position = format!(" at line {} column {}", line, column);
+    let message = message
+        .strip_suffix(position.as_str())
+        .map(String::from)
+        .unwrap_or(message);
+    Error::DefinitionError(message, line, column)
+}
+
+/// The (1-based) line and column right after the last character of the document.
+fn end_of_document(document: &str) -> (usize, usize) {
+    let lines: Vec<&str> = document.split('\n').collect();
+    let last = lines.last().map_or(0, |l| l.chars().count());
+    (lines.len(), last + 1)
+}
+
+fn named_callbacks<S: StateValue, E: EventValue>(names: &[String]) -> Vec<TriggerFunction<S, E>> {
+    names.iter().map(|n| TriggerFunction::named(n)).collect()
+}
+
+fn callback_names<S: StateValue, E: EventValue>(
+    entry: &str,
+    callbacks: &[TriggerFunction<S, E>],
+) -> Result<Vec<String>> {
+    let names = callbacks
+        .iter()
+        .map(|func| func.name().map(String::from))
+        .collect();
+    exported(entry.to_string(), names)
+}
+
+/// The names of the callbacks or conditions of an exported entry. None stands for a callable that
+///     cannot be referred to by name.
+fn exported(entry: String, names: Option<Vec<String>>) -> Result<Vec<String>> {
+    names.ok_or_else(|| {
+        Error::ExportError(format!(
+            "{} contains a callable without name or a combined condition",
+            entry
+        ))
+    })
+}
+
+fn named_conditions<S: StateValue, E: EventValue>(
+    names: &[String],
+) -> Vec<ConditionFunction<S, E>> {
+    names.iter().map(|n| ConditionFunction::named(n)).collect()
+}
+
+impl StateDefinition {
+    fn to_state<S: StateValue, E: EventValue>(&self) -> Result<State<S, E>> {
+        let mut state: State<S, E> = self.name.parse()?;
+        for callback in named_callbacks(&self.on_enter) {
+            state.add_callback(StateTrigger::EnterTrigger(callback));
+        }
+        for callback in named_callbacks(&self.on_exit) {
+            state.add_callback(StateTrigger::ExitTrigger(callback));
+        }
+        state.ignore_invalid_triggers = self.ignore_invalid_triggers;
+        Ok(state)
+    }
+}
+
+impl TransitionDefinition {
+    fn to_parameters<S: StateValue, E: EventValue>(&self) -> TransitionParameters<S, E> {
+        let source: Vec<&str> = self.source.iter().map(|s| s.as_str()).collect();
+        TransitionParameters::from_names(&self.trigger, &source, self.dest.as_deref())
+            .conditions(PotentialConditions::from(named_conditions(
+                &self.conditions,
+            )))
+            .unless(PotentialConditions::from(named_conditions(&self.unless)))
+            .before(PotentialTriggers::from(named_callbacks(&self.before)))
+            .after(PotentialTriggers::from(named_callbacks(&self.after)))
+            .prepare(PotentialTriggers::from(named_callbacks(&self.prepare)))
+    }
+}
+
+impl MachineDefinition {
+    /// Check that all states referred to by transitions have been defined and that all callbacks
+    ///     and conditions are part of the passed registry and match ``send_event``. Errors name the offending entry along with
+    ///     the value, e.g. 'transitions[0].before: missing'.
+    ///         Args:
+    ///             registry (CallbackRegistry): The callbacks and conditions named in the definition.
+    ///         Raises:
+    ///             UnknownStateError: A state name cannot be converted into a state value or a transition
+    ///                 refers to a state that has not been defined. The initial state is added to the
+    ///                 machine if necessary and is always known.
+    ///             UnknownEventError: A transition is triggered by an event not named by ``EventValue::event_names``.
+    ///             CallableError: A callback or condition is missing from the registry.
+    ///             MachineError: A callback or condition does not match ``send_event``.
+    pub fn validate<S: StateValue, E: EventValue>(
+        &self,
+        registry: &CallbackRegistry<S, E>,
+    ) -> Result<()> {
+        let states = self.states.iter().enumerate();
+        let names = states.map(|(i, s)| (format!("states[{}].name", i), &s.name));
+        for (entry, name) in self
+            .initial
+            .iter()
+            .map(|i| (String::from("initial"), i))
+            .chain(names)
+        {
+            if name.parse::<S>().is_err() {
+                return Err(Error::UnknownStateError(format!("{}: {}", entry, name)));
+            }
+        }
+
+        let known_state = |name: &String| {
+            self.states.iter().any(|s| &s.name == name) || self.initial.as_ref() == Some(name)
+        };
+        // machines with statically known events only accept those
+        let events = E::event_names();
+        for (i, transition) in self.transitions.iter().enumerate() {
+            let trigger = transition.trigger.as_str();
+            if !events.is_empty() && !events.contains(&trigger) {
+                return Err(Error::UnknownEventError(format!(
+                    "transitions[{}].trigger: {}",
+                    i, trigger
+                )));
+            }
+            let sources = transition.source.iter().filter(|s| s.as_str() != "*");
+            let dest = transition.dest.iter().filter(|d| d.as_str() != "=");
+            let states = sources
+                .map(|s| ("source", s))
+                .chain(dest.map(|d| ("dest", d)));
+            for (key, state) in states {
+                if !known_state(state) {
+                    return Err(Error::UnknownStateError(format!(
+                        "transitions[{}].{}: {}",
+                        i, key, state
+                    )));
+                }
+            }
+        }
+
+        let mut callbacks: Vec<(String, &[String])> = Vec::new();
+        for (i, state) in self.states.iter().enumerate() {
+            callbacks.push((format!("states[{}].on_enter", i), &state.on_enter));
+            callbacks.push((format!("states[{}].on_exit", i), &state.on_exit));
+        }
+        for (i, transition) in self.transitions.iter().enumerate() {
+            callbacks.push((format!("transitions[{}].before", i), &transition.before));
+            callbacks.push((format!("transitions[{}].after", i), &transition.after));
+            callbacks.push((format!("transitions[{}].prepare", i), &transition.prepare));
+        }
+        callbacks.push((
+            String::from("before_state_change"),
+            &self.before_state_change,
+        ));
+        callbacks.push((String::from("after_state_change"), &self.after_state_change));
+        callbacks.push((String::from("prepare_event"), &self.prepare_event));
+        callbacks.push((String::from("finalize_event"), &self.finalize_event));
+        callbacks.push((String::from("on_exception"), &self.on_exception));
+        for (entry, names) in callbacks {
+            for name in names {
+                match registry.callback(name) {
+                    None => return Err(Error::CallableError(format!("{}: {}", entry, name))),
+                    Some(func) if !func.is_named() && func.sends_event() != self.send_event => {
+                        return Err(Error::MachineError(format!("{}: {}", entry, name)))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        for (i, transition) in self.transitions.iter().enumerate() {
+            let conditions = [
+                ("conditions", &transition.conditions),
+                ("unless", &transition.unless),
+            ];
+            for (key, names) in conditions.iter() {
+                for name in names.iter() {
+                    let entry = format!("transitions[{}].{}: {}", i, key, name);
+                    match registry.condition(name) {
+                        None => return Err(Error::CallableError(entry)),
+                        Some(func) if !func.is_named() && func.sends_event() != self.send_event => {
+                            return Err(Error::MachineError(entry))
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: StateValue, E: EventValue> Machine<S, E> {
+    /// Create a machine from a definition. Callbacks and conditions are referred to by name and
+    ///     resolved through the passed registry when an event is processed. The definition is
+    ///     validated first, see ``MachineDefinition::validate``.
+    ///         Args:
+    ///             definition (MachineDefinition): The states, transitions and settings of the machine.
+    ///             registry (CallbackRegistry): The callbacks and conditions named in the definition.
+    ///                 Callbacks named after dynamic methods such as 'on_enter_<state>' are bound as well.
+    pub fn from_definition(
+        definition: MachineDefinition,
+        registry: CallbackRegistry<S, E>,
+    ) -> Result<Self> {
+        definition.validate(&registry)?;
+        let states = definition
+            .states
+            .iter()
+            .map(StateDefinition::to_state)
+            .collect::<Result<Vec<_>>>()?;
+        let initial = definition
+            .initial
+            .as_deref()
+            .map(str::parse::<State<S, E>>)
+            .transpose()?;
+        let transitions = definition
+            .transitions
+            .iter()
+            .map(TransitionDefinition::to_parameters)
+            .collect();
+        let mut machine = Machine::new(
+            None,
+            states,
+            initial,
+            transitions,
+            definition.send_event,
+            definition.auto_transitions,
+            definition.ordered_transitions,
+            definition.ignore_invalid_triggers.unwrap_or(false),
+            None,
+            None,
+            definition.name,
+            definition.queued,
+            None,
+            None,
+            definition.model_attribute,
+            None,
+        )?;
+        machine
+            .before_state_change
+            .extend(named_callbacks(&definition.before_state_change));
+        machine
+            .after_state_change
+            .extend(named_callbacks(&definition.after_state_change));
+        machine
+            .prepare_event
+            .extend(named_callbacks(&definition.prepare_event));
+        machine
+            .finalize_event
+            .extend(named_callbacks(&definition.finalize_event));
+        machine
+            .on_exception
+            .extend(named_callbacks(&definition.on_exception));
+        machine.set_registry(registry)?;
+        Ok(machine)
+    }
+
+    /// Create a machine from a JSON document in the format of pytransitions' dict based configuration.
+    ///     See ``Machine::from_definition``.
+    ///         Args:
+    ///             json (str): The definition.
+    ///             registry (CallbackRegistry): The callbacks and conditions named in the definition.
+    pub fn from_json(json: &str, registry: CallbackRegistry<S, E>) -> Result<Self> {
+        let definition: MachineDefinition = serde_json::from_str(json).map_err(|err| {
+            definition_error(err.to_string(), Some((err.line(), err.column())), json)
+        })?;
+        Machine::from_definition(definition, registry)
+    }
+
+    /// Create a machine from a YAML document using the same schema as ``Machine::from_json``.
+    ///         Args:
+    ///             yaml (str): The definition.
+    ///             registry (CallbackRegistry): The callbacks and conditions named in the definition.
+    pub fn from_yaml(yaml: &str, registry: CallbackRegistry<S, E>) -> Result<Self> {
+        let definition: MachineDefinition = serde_yaml::from_str(yaml).map_err(|err| {
+            let location = err.location().map(|l| (l.line(), l.column()));
+            definition_error(err.to_string(), location, yaml)
+        })?;
+        Machine::from_definition(definition, registry)
+    }
+
+    /// Export the machine into a definition of the shape pytransitions' ``MarkupMachine`` produces.
+    ///     Loading the definition with the same registry results in an equivalent machine.
+    ///     Callbacks and conditions are referred to by name. Auto transitions are omitted since they
+    ///     are added again while loading, and ordered or wildcard transitions are exported as the
+    ///     transitions they have been expanded to.
+    ///         Raises:
+    ///             ExportError: A callback or condition has no name or conditions have been combined with
+    ///                 ``Condition::any`` or ``Condition::all``. Neither can be referred to by a definition.
+    pub fn to_definition(&self) -> Result<MachineDefinition> {
+        let mut states = Vec::new();
+        for (i, state) in self.states().enumerate() {
+            let entry = |key: &str| format!("states[{}].{}", i, key);
+            states.push(StateDefinition {
+                name: state.name().to_string(),
+                on_enter: exported(
+                    entry("on_enter"),
+                    state.callback_names(StateTriggerType::Enter),
+                )?,
+                on_exit: exported(
+                    entry("on_exit"),
+                    state.callback_names(StateTriggerType::Exit),
+                )?,
+                ignore_invalid_triggers: state.ignore_invalid_triggers(),
+            });
+        }
+
+        let mut transitions = Vec::new();
+        for event in self.events.values() {
+            for transition in event.all_transitions().filter(|t| !t.auto) {
+                let entry = |key: &str| {
+                    format!(
+                        "transitions[{} from {}].{}",
+                        event.name(),
+                        transition.source(),
+                        key
+                    )
+                };
+                let callbacks = |key: &str, trigger: TransitionTriggerType| {
+                    exported(entry(key), transition.callback_names(trigger))
+                };
+                transitions.push(TransitionDefinition {
+                    trigger: event.name().to_string(),
+                    source: vec![transition.source().to_string()],
+                    dest: transition.dest().map(String::from),
+                    conditions: exported(entry("conditions"), transition.condition_names(true))?,
+                    unless: exported(entry("unless"), transition.condition_names(false))?,
+                    before: callbacks("before", TransitionTriggerType::Before)?,
+                    after: callbacks("after", TransitionTriggerType::After)?,
+                    prepare: callbacks("prepare", TransitionTriggerType::Prepare)?,
+                });
+            }
+        }
+
+        let name = self.name.trim_end_matches(": ");
+        Ok(MachineDefinition {
+            name: Some(name.to_string()).filter(|name| !name.is_empty()),
+            states,
+            transitions,
+            initial: self.initial().map(String::from),
+            auto_transitions: self.auto_transitions,
+            ordered_transitions: false,
+            send_event: self.send_event,
+            ignore_invalid_triggers: Some(self.ignore_invalid_triggers),
+            queued: self.queued,
+            model_attribute: Some(self.model_attribute.clone()),
+            before_state_change: callback_names("before_state_change", &self.before_state_change)?,
+            after_state_change: callback_names("after_state_change", &self.after_state_change)?,
+            prepare_event: callback_names("prepare_event", &self.prepare_event)?,
+            finalize_event: callback_names("finalize_event", &self.finalize_event)?,
+            on_exception: callback_names("on_exception", &self.on_exception)?,
+        })
+    }
+
+    /// Export the machine as JSON document. See ``Machine::to_definition``.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.to_definition()?)
+            .map_err(|err| Error::ExportError(err.to_string()))
+    }
+}
@@ -1,10 +1,11 @@
-//!
+//! The errors raised by a ``Machine`` and its components.
 
 use crate::support::KeySet;
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 #[non_exhaustive]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     /// MachineError is used for issues related to state transitions and current states.
     /// For instance, it is raised for invalid transitions or machine configuration issues.
@@ -24,7 +25,7 @@ pub enum Error {
     TriggerNameError(String),
     #[error("InsufficientStatesError: Can't create ordered transitions on a Machine with fewer than 2 states.")]
     InsufficientStatesError,
-    #[error("CallableError: Callable with name '{0}' could neither be retrieved from the passed model nor imported from a module.")]
+    #[error("CallableError: Callable with name '{0}' could not be retrieved from the callback registry.")]
     CallableError(String),
     #[error("UnknownStateError: State {0} has not been added to the machine")]
     UnknownStateError(String),
@@ -32,5 +33,14 @@ pub enum Error {
     MachineAttributeError(String, String),
     #[error("event '{0}' is not registered on <Machine@{1}>")]
     MachineEventAttributeError(String, String),
+    /// DefinitionError is raised when a machine definition cannot be parsed. It contains the
+    /// reason as well as the line and column (starting at 1) the problem has been detected at. Problems
+    /// the parser cannot locate, e.g. an empty document, are reported at the end of the document.
+    #[error("DefinitionError: {0} at line {1} column {2}")]
+    DefinitionError(String, usize, usize),
+    /// ExportError is raised when a machine cannot be exported into a definition, e.g. because a
+    /// callback has no name. It names the offending entry.
+    #[error("ExportError: {0}")]
+    ExportError(String),
     // #[error("{0}")]
 }
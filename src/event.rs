@@ -1,9 +1,110 @@
-//!
+//! Events and the data that is passed along while an event is being processed.
 
-use crate::core::{State, Transition, TransitionTriggerType, TriggerFunction};
+use crate::core::{State, StateValue, Transition, TransitionTriggerType, TriggerFunction};
 use crate::error::Error;
-use crate::machine::{Machine, Model};
+use crate::machine::{Machine, ModelRef};
+use crate::Result;
+use indexmap::IndexMap;
+use log::{debug, error, warn};
+use std::any::Any;
 use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::rc::Rc;
+
+/// The values a ``Machine`` uses to identify its events. Besides ``String`` this can be an
+///     enumeration deriving strum's ``AsRefStr``, whose string form is the name of the triggered
+///     event. Variants may carry data which is made available to callbacks via
+///     ``EventData::event_value`` and ``EventArgs::event_value``.
+pub trait EventValue: Any + AsRef<str> + Clone + Debug {
+    /// The names of all events of this type. Machines register these events upfront, so that
+    ///     triggering one of them never raises an ``UnknownEventError``, and refuse transitions
+    ///     for any other event. Enumerations usually return strum's ``VARIANTS`` here. The default
+    ///     (no names) allows events of any name, as required for ``String``.
+    fn event_names() -> &'static [&'static str] {
+        &[]
+    }
+}
+
+impl EventValue for String {}
+
+/// Optional positional and keyword arguments passed along with a trigger. Values can be of any
+///     type and are retrieved by their type, e.g. ``args.kwarg::<u32>("amount")``.
+///     Attributes:
+///         args (list): Positional arguments in the order they have been added.
+///         kwargs (dict): Keyword arguments.
+///         event_value (EventValue): The value the event has been triggered with, if any.
+#[derive(Clone, Default)]
+pub struct EventArgs {
+    args: Vec<Rc<dyn Any>>,
+    kwargs: IndexMap<String, Rc<dyn Any>>,
+    pub(crate) event_value: Option<Rc<dyn Any>>,
+}
+
+impl EventArgs {
+    /// Create an empty set of arguments.
+    pub fn new() -> Self {
+        EventArgs::default()
+    }
+
+    /// Append a positional argument.
+    pub fn with_arg<T: Any>(mut self, value: T) -> Self {
+        self.args.push(Rc::new(value));
+        self
+    }
+
+    /// Add (or replace) a keyword argument.
+    pub fn with_kwarg<T: Any>(mut self, key: &str, value: T) -> Self {
+        self.kwargs.insert(key.to_string(), Rc::new(value));
+        self
+    }
+
+    /// Return the positional argument at ``index`` if it exists and is of type ``T``.
+    pub fn arg<T: Any>(&self, index: usize) -> Option<&T> {
+        self.args.get(index).and_then(|v| v.downcast_ref::<T>())
+    }
+
+    /// Return the keyword argument named ``key`` if it exists and is of type ``T``.
+    pub fn kwarg<T: Any>(&self, key: &str) -> Option<&T> {
+        self.kwargs.get(key).and_then(|v| v.downcast_ref::<T>())
+    }
+
+    /// Return the value the event has been triggered with if it is of type ``E``.
+    pub fn event_value<E: EventValue>(&self) -> Option<&E> {
+        self.event_value
+            .as_ref()
+            .and_then(|v| v.downcast_ref::<E>())
+    }
+
+    pub(crate) fn with_event_value<E: EventValue>(mut self, value: E) -> Self {
+        self.event_value = Some(Rc::new(value));
+        self
+    }
+
+    /// The number of positional and keyword arguments.
+    pub fn len(&self) -> usize {
+        self.args.len() + self.kwargs.len()
+    }
+
+    /// True if neither positional nor keyword arguments have been passed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The names of all keyword arguments.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.kwargs.keys().map(|k| k.as_str())
+    }
+}
+
+impl Debug for EventArgs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "EventArgs(args={}, kwargs={:?})",
+            self.args.len(),
+            self.kwargs.keys().collect::<Vec<_>>()
+        )
+    }
+}
 
 /// Collection of relevant data related to the ongoing transition attempt.
 ///     Attributes:
@@ -19,19 +120,18 @@ use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
 ///         error (Error): In case a triggered event causes an Error, it is assigned here and passed on.
 ///         result (bool): True in case a transition has been successful, False otherwise.
 #[allow(dead_code)]
-pub struct EventData<'a, 'b: 'a> {
-    state: &'a State,
-    event: &'a Event<'b>,
-    pub(crate) machine: &'a mut Machine,
-    pub(crate) model: &'a Model,
-    //*args
-    //**kwargs_
-    transition: Option<&'a Transition>,
-    error: Option<Error>,
-    result: bool,
+pub struct EventData<'a, S = String, E = String> {
+    pub(crate) state: &'a State<S, E>,
+    pub(crate) event: &'a Event<S, E>,
+    pub(crate) machine: &'a Machine<S, E>,
+    pub(crate) model: &'a ModelRef,
+    pub(crate) args: EventArgs,
+    pub(crate) transition: Option<&'a Transition<S, E>>,
+    pub(crate) error: Option<Error>,
+    pub(crate) result: bool,
 }
 
-impl<'a, 'b> EventData<'a, 'b> {
+impl<'a, S: StateValue, E: EventValue> EventData<'a, S, E> {
     ///         Args:
     ///             state (State): The State from which the Event was triggered.
     ///             event (Event): The triggering Event.
@@ -43,19 +143,18 @@ impl<'a, 'b> EventData<'a, 'b> {
     ///                 to store internally for possible later use.
     #[allow(dead_code)]
     pub fn new(
-        state: &'a State,
-        event: &'b Event,
-        machine: &'a mut Machine,
-        model: &'a Model,
+        state: &'a State<S, E>,
+        event: &'a Event<S, E>,
+        machine: &'a Machine<S, E>,
+        model: &'a ModelRef,
+        args: EventArgs,
     ) -> Self {
-        //, args, kwargs
-        // self.args = args
-        // self.kwargs = kwargs
         EventData {
             state,
             event,
             machine,
             model,
+            args,
             error: None,
             result: false,
             transition: None,
@@ -64,17 +163,65 @@ impl<'a, 'b> EventData<'a, 'b> {
 
     /// Updates the EventData object with the passed state.
     ///         Attributes:
-    ///             state (State, str or Enum): The state object, enum member or string to assign to EventData.
-    pub fn update(&mut self, _state: State) {
-        // if not isinstance(state, State):
-        // self.state = self.machine.get_state(state)
-        todo!()
+    ///             state (State): The state object to assign to EventData.
+    pub fn update(&mut self, state: &'a State<S, E>) {
+        self.state = state;
+    }
+
+    /// The State from which the Event was triggered.
+    pub fn state(&self) -> &State<S, E> {
+        self.state
+    }
+
+    /// The triggering Event.
+    pub fn event(&self) -> &Event<S, E> {
+        self.event
+    }
+
+    /// The current Machine instance.
+    pub fn machine(&self) -> &Machine<S, E> {
+        self.machine
+    }
+
+    /// The model the machine is bound to.
+    pub fn model(&self) -> &ModelRef {
+        self.model
+    }
+
+    /// The arguments passed along with the trigger.
+    pub fn args(&self) -> &EventArgs {
+        &self.args
+    }
+
+    /// The value the event has been triggered with, e.g. an enumeration variant carrying data.
+    ///         Returns None if the event has not been triggered via the ``Machine``.
+    pub fn event_value(&self) -> Option<&E> {
+        self.args.event_value()
+    }
+
+    /// Currently active transition, if one has been assigned yet.
+    pub fn transition(&self) -> Option<&Transition<S, E>> {
+        self.transition
+    }
+
+    /// In case a triggered event caused an Error, it is assigned here. ``on_exception`` callbacks
+    ///     may clone it to re-raise the original error.
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    /// True in case a transition has been successful, False otherwise.
+    pub fn result(&self) -> bool {
+        self.result
     }
 }
 
-impl Display for EventData<'_, '_> {
-    fn fmt(&self, _f: &mut Formatter<'_>) -> FmtResult {
-        unimplemented!()
+impl<S: StateValue, E: EventValue> Display for EventData<'_, S, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.transition {
+            Some(transition) => write!(f, "EventData('{}', {})", self.state.name(), transition),
+            None => write!(f, "EventData('{}', None)", self.state.name()),
+        }
     }
     // def __repr__(self):
     // return "<%s('%s', %s)@%s>" % (type(self).__name__, self.state,
@@ -83,22 +230,27 @@ impl Display for EventData<'_, '_> {
 
 #[derive(Debug)]
 /// A collection of transitions assigned to the same trigger
-pub struct Event<'a> {
+pub struct Event<S = String, E = String> {
     name: String,
-    machine: &'a Machine,
-    //transitions
+    transitions: IndexMap<String, Vec<Transition<S, E>>>,
 }
 
-impl<'a> Event<'a> {
+impl<S: StateValue, E: EventValue> Event<S, E> {
     ///         Args:
     ///             name (str): The name of the event, which is also the name of the
     ///                 triggering callable (e.g., 'advance' implies an advance()
     ///                 method).
-    ///             machine (Machine): The current Machine instance.
     #[allow(dead_code)]
-    pub fn new(name: String, machine: &'a Machine) -> Self {
-        Event { name, machine }
-        // self.transitions = defaultdict(list)
+    pub fn new(name: String) -> Self {
+        Event {
+            name,
+            transitions: IndexMap::new(),
+        }
+    }
+
+    /// The name of the event.
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
     /// Add a transition to the list of potential transitions.
@@ -106,66 +258,164 @@ impl<'a> Event<'a> {
     ///             transition (Transition): The Transition instance to add to the
     ///                 list.
     #[allow(dead_code)]
-    pub fn add_transition(self, _transition: Transition) {
-        // self.transitions[transition.source].append(transition)
+    pub fn add_transition(&mut self, transition: Transition<S, E>) {
+        self.transitions
+            .entry(transition.source().to_string())
+            .or_default()
+            .push(transition);
+    }
+
+    /// Return the transitions with the passed source state in the order they have been added.
+    pub fn transitions(&self, source: &str) -> &[Transition<S, E>] {
+        self.transitions
+            .get(source)
+            .map(|t| t.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Keep only the transitions for which ``keep`` returns True. Sources without any transitions left
+    ///     are removed.
+    pub(crate) fn retain_transitions<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&Transition<S, E>) -> bool,
+    {
+        for transitions in self.transitions.values_mut() {
+            transitions.retain(|t| keep(t));
+        }
+        self.transitions
+            .retain(|_, transitions| !transitions.is_empty());
+    }
+
+    /// All transitions of this event in the order their sources have been added.
+    pub(crate) fn all_transitions(&self) -> impl Iterator<Item = &Transition<S, E>> {
+        self.transitions.values().flatten()
+    }
+
+    /// Indicates if no transitions are left in this event.
+    pub fn is_empty(&self) -> bool {
+        self.transitions.is_empty()
     }
 
     /// Serially execute all transitions that match the current state,
     ///         halting as soon as one successfully completes.
     ///         Args:
-    ///             args and kwargs: Optional positional or named arguments that will
+    ///             machine (Machine): The machine this event is registered on.
+    ///             model (object): The model whose state should change.
+    ///             args (EventArgs): Optional positional or named arguments that will
     ///                 be passed onto the EventData object, enabling arbitrary state
     ///                 information to be passed on to downstream triggered functions.
     ///         Returns: boolean indicating whether or not a transition was
     ///             successfully executed (True if successful, False if not).
     #[allow(dead_code)]
-    pub fn trigger(&self, _model: &Model) { // *args, **kwargs,
-                                            // func = partial(self._trigger, model, *args, **kwargs)
-                                            // # pylint: disable=protected-access
-                                            // # noinspection PyProtectedMember
-                                            // # Machine._process should not be called somewhere else. That's why it should not be exposed
-                                            // # to Machine users.
-                                            // return self.machine._process(func)
+    pub fn trigger(
+        &self,
+        machine: &Machine<S, E>,
+        model: &ModelRef,
+        args: EventArgs,
+    ) -> Result<bool> {
+        // Machine._process should not be called somewhere else. That's why it should not be exposed
+        // to Machine users.
+        let name = self.name.clone();
+        let model = model.clone();
+        machine._process(Box::new(move |machine: &Machine<S, E>| {
+            machine
+                .get_event(&name)?
+                .machine_trigger(machine, &model, args)
+        }))
     }
 
     /// Internal trigger function called by the ``Machine`` instance. This should not
     ///         be called directly but via the public method ``Machine.trigger``.
     #[allow(dead_code)]
-    pub fn machine_trigger(&self, _model: Model) {
-        // *args, **kwargs
-        // state = self.machine.get_model_state(model)
-        // if state.name not in self.transitions:
-        // msg = "%sCan't trigger event %s from state %s!" % (self.machine.name, self.name,
-        // state.name)
-        // ignore = state.ignore_invalid_triggers if state.ignore_invalid_triggers is not None \
-        // else self.machine.ignore_invalid_triggers
-        // if ignore:
-        // _LOGGER.warning(msg)
-        // return False
-        // else:
-        // raise MachineError(msg)
-        // event_data = EventData(state, self, self.machine, model, args=args, kwargs=kwargs)
-        // return self._process(event_data)
-    }
-
-    pub fn _process(&self, _event_data: &EventData) {
-        // self.machine.callbacks(self.machine.prepare_event, event_data)
-        // _LOGGER.debug("%sExecuted machine preparation callbacks before conditions.", self.machine.name)
-
-        // try:
-        // for trans in self.transitions[event_data.state.name]:
-        // event_data.transition = trans
-        // if trans.execute(event_data):
-        // event_data.result = True
-        // break
-        // except Exception as err:
-        // event_data.error = err
-        // raise
-        // finally:
-        // self.machine.callbacks(self.machine.finalize_event, event_data)
-        // _LOGGER.debug("%sExecuted machine finalize callbacks", self.machine.name)
-
-        // return event_data.result
+    pub(crate) fn machine_trigger(
+        &self,
+        machine: &Machine<S, E>,
+        model: &ModelRef,
+        args: EventArgs,
+    ) -> Result<bool> {
+        let state = machine.get_model_state(model)?;
+        let mut event_data = EventData::new(state, self, machine, model, args);
+        self._process(&mut event_data)
+    }
+
+    fn _process<'a>(&'a self, event_data: &mut EventData<'a, S, E>) -> Result<bool> {
+        let machine = event_data.machine;
+        let mut raised = None;
+        let mut reraise = false;
+        if let Err(err) = self._process_transitions(event_data) {
+            event_data.error = Some(err);
+            if machine.on_exception.is_empty() {
+                reraise = true;
+            } else {
+                let on_exception: Vec<_> = machine.on_exception.iter().collect();
+                raised = machine.callbacks(on_exception.as_slice(), event_data).err();
+            }
+        }
+
+        let finalize: Vec<_> = machine.finalize_event.iter().collect();
+        if let Err(err) = machine.callbacks(finalize.as_slice(), event_data) {
+            error!(
+                "{}While executing finalize callbacks an error occurred: {}.",
+                machine.name, err
+            );
+        }
+        debug!("{}Executed machine finalize callbacks", machine.name);
+
+        if reraise {
+            raised = event_data.error.take();
+        }
+        match raised {
+            Some(err) => Err(err),
+            None => Ok(event_data.result),
+        }
+    }
+
+    fn _is_valid_source(&self, event_data: &EventData<'_, S, E>) -> Result<bool> {
+        let machine = event_data.machine;
+        let state = event_data.state;
+        if self.transitions.contains_key(state.name()) {
+            return Ok(true);
+        }
+        let msg = format!(
+            "{}Can't trigger event {} from state {}!",
+            machine.name,
+            self.name,
+            state.name()
+        );
+        let ignore = state
+            .ignore_invalid_triggers()
+            .unwrap_or(machine.ignore_invalid_triggers);
+        if ignore {
+            warn!("{}", msg);
+            Ok(false)
+        } else {
+            Err(Error::MachineError(msg))
+        }
+    }
+
+    fn _process_transitions<'a>(&'a self, event_data: &mut EventData<'a, S, E>) -> Result<()> {
+        let machine = event_data.machine;
+        // invalid triggers are checked here so that the error ends up on the event data
+        // and finalize callbacks run for them as well
+        if !self._is_valid_source(event_data)? {
+            return Ok(());
+        }
+        let prepare: Vec<_> = machine.prepare_event.iter().collect();
+        machine.callbacks(prepare.as_slice(), event_data)?;
+        debug!(
+            "{}Executed machine preparation callbacks before conditions.",
+            machine.name
+        );
+
+        let state = event_data.state;
+        for trans in self.transitions[state.name()].iter() {
+            event_data.transition = Some(trans);
+            if trans.execute(event_data)? {
+                event_data.result = true;
+                break;
+            }
+        }
+        Ok(())
     }
 
     // Add a new before or after callback to all available transitions.
@@ -174,16 +424,24 @@ impl<'a> Event<'a> {
     ///                 'before', 'after' or 'prepare'.
     ///             func (str): The name of the callback function.
     #[allow(dead_code)]
-    pub fn add_callback(self, _trigger: TransitionTriggerType, _func: TriggerFunction) {
-        // for trans in itertools.chain(*self.transitions.values()):
-        // trans.add_callback(trigger, func)
+    pub fn add_callback(&mut self, trigger: TransitionTriggerType, func: TriggerFunction<S, E>) {
+        for trans in self.transitions.values_mut().flatten() {
+            trans.add_callback(trigger, func.clone());
+        }
+    }
+
+    /// Add a callback referred to by name to all transitions which do not mention it yet.
+    pub(crate) fn add_named_callback(&mut self, trigger: TransitionTriggerType, name: &str) {
+        for trans in self.transitions.values_mut().flatten() {
+            if !trans.has_callback(name) {
+                trans.add_callback(trigger, TriggerFunction::named(name));
+            }
+        }
     }
 }
 
-impl Display for Event<'_> {
+impl<S, E> Display for Event<S, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        Debug::fmt(self, f)
+        write!(f, "Event('{}')", self.name)
     }
-    // def __repr__(self):
-    // return "<%s('%s')@%s>" % (type(self).__name__, self.name, id(self))
 }
@@ -1,11 +1,24 @@
-//!
+//! A Rust State Machine Library Modeled After PyTransitions
 
 mod core;
+mod definition;
 mod error;
 mod event;
 mod machine;
+mod registry;
 mod support;
 
+pub use crate::core::{
+    Condition, ConditionFunction, PotentialConditions, PotentialTriggers, State, StateTrigger,
+    StateTriggerType, StateValue, Transition, TransitionParameters, TransitionTriggerType,
+    TriggerFunction,
+};
+pub use crate::definition::{MachineDefinition, StateDefinition, TransitionDefinition};
+pub use crate::error::Error;
+pub use crate::event::{Event, EventArgs, EventData, EventValue};
+pub use crate::machine::{Machine, MachineModel, Model, ModelRef};
+pub use crate::registry::CallbackRegistry;
+
 // _LOGGER = logging.getLogger(__name__)
 // _LOGGER.addHandler(logging.NullHandler())
 
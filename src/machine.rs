@@ -1,20 +1,81 @@
-//!
+//! The ``Machine`` which manages states, events and the models attached to it.
 
-use crate::core::{ConditionFunction, State, Transition, TransitionParameters, TriggerFunction}; //StateTrigger,ConditionFunction,
-use crate::event::EventData;
-// use indexmap::IndexMap;
+use crate::core::{
+    ConditionFunction, PotentialConditions, PotentialTriggers, State, StateTrigger,
+    StateTriggerType, StateValue, Transition, TransitionParameters, TransitionTriggerType,
+    TriggerFunction,
+};
+use crate::error::Error;
+use crate::event::{Event, EventArgs, EventData, EventValue};
+use crate::registry::CallbackRegistry;
+use crate::support::_prep_ordered_arg;
+use crate::Result;
+use indexmap::IndexMap;
 use log::info;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::rc::Rc;
+use std::str::FromStr;
 
-pub(crate) type Model = Machine;
+/// An object whose state is managed by one or more ``Machine`` instances. The state is stored
+///     under the ``model_attribute`` of the machine, which allows a single model to be driven by
+///     several machines at once.
+pub trait Model: Debug {
+    /// Return the name of the current state stored under ``model_attribute``, if one has been set.
+    fn get_state(&self, model_attribute: &str) -> Option<String>;
 
-pub fn getattr(_model: &Model, _attribute: &str) -> State {
-    todo!()
+    /// Store the name of the current state under ``model_attribute``.
+    fn set_state(&mut self, model_attribute: &str, state: String);
+}
+
+/// A shared handle to a model. Models are shared between the ``Machine`` and its users.
+pub type ModelRef = Rc<RefCell<dyn Model>>;
+
+/// The model used when a ``Machine`` is created without one, so that the machine acts as its own
+///     model. It only keeps track of its state(s).
+#[derive(Debug, Default)]
+pub struct MachineModel {
+    states: HashMap<String, String>,
+}
+
+impl Model for MachineModel {
+    fn get_state(&self, model_attribute: &str) -> Option<String> {
+        self.states.get(model_attribute).cloned()
+    }
+
+    fn set_state(&mut self, model_attribute: &str, state: String) {
+        self.states.insert(model_attribute.to_string(), state);
+    }
+}
+
+/// A trigger waiting to be processed by a queued ``Machine``.
+pub(crate) type QueuedTrigger<S, E> = Box<dyn FnOnce(&Machine<S, E>) -> Result<bool>>;
+
+/// The transition queue of a ``Machine``. The entry at the front is the one currently being
+///     processed; it is only removed once it has finished.
+struct TransitionQueue<S, E>(RefCell<VecDeque<Option<QueuedTrigger<S, E>>>>);
+
+impl<S, E> Default for TransitionQueue<S, E> {
+    fn default() -> Self {
+        TransitionQueue(RefCell::new(VecDeque::new()))
+    }
+}
+
+impl<S, E> Debug for TransitionQueue<S, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "TransitionQueue(len={})", self.0.borrow().len())
+    }
 }
 
 /// Machine manages states, transitions and ;
 /// models. In case it is initialized without a specific model
 ///     (or specifically no model), it will also act as a model itself. Machine takes also care of decorating
 ///     models with conveniences functions related to added transitions and states during runtime.
+///     States are identified by strings unless another ``StateValue`` such as an enumeration is used.
+///     Events are identified by strings as well unless another ``EventValue`` is used. An enumeration naming
+///     all its variants in ``EventValue::event_names`` restricts the machine to these events; auto transitions
+///     are only added for states with a matching 'to_<state>' variant then.
 ///     Attributes:
 ///         states (OrderedDict): Collection of all registered states.
 ///         events (dict): Collection of transitions ordered by trigger/event.
@@ -26,11 +87,15 @@ pub fn getattr(_model: &Model, _attribute: &str) -> State {
 ///         after_state_change (list): Callbacks executed after the transition has been conducted.
 ///             Callbacks will be executed AFTER the custom callbacks assigned to the transition.
 ///         finalize_event (list): Callbacks will be executed after all transitions callbacks have been executed.
-///             Callbacks mentioned here will also be called if a transition or condition check raised an error.
+///             Callbacks mentioned here will also be called if a transition or condition check raised an error
+///             or the trigger is invalid (or ignored) in the current state.
 ///         queued (bool): Whether transitions in callbacks should be executed immediately (False) or sequentially.
 ///         send_event (bool): When True, any arguments passed to trigger methods will be wrapped in an EventData
 ///             object, allowing indirect and encapsulated access to data. When False, all positional and keyword
-///             arguments will be passed directly to all callback methods.
+///             arguments will be passed directly to all callback methods, which have to be created with
+///             ``TriggerFunction::with_args`` or ``ConditionFunction::with_args`` in that case. Callables of the
+///             other shape are rejected with a ``MachineError`` when they are added to the machine, or when
+///             the event is processed if they are referred to by name.
 ///         auto_transitions (bool):  When True (default), every state will automatically have an associated
 ///             to_{state}() convenience trigger in the base model.
 ///         ignore_invalid_triggers (bool): When True, any calls to trigger methods that are not valid for the
@@ -38,37 +103,39 @@ pub fn getattr(_model: &Model, _attribute: &str) -> State {
 ///             ignored rather than raising an invalid transition exception.
 ///         name (str): Name of the ``Machine`` instance mainly used for easier log message distinction.
 #[derive(Debug)]
-pub struct Machine {
-    // states: IndexMap<String, State>,
-    // initial: String,
-    models: Vec<Model>,
-    //events
-    // prepare_event////
-    pub before_state_change: Vec<TriggerFunction>,
-    pub after_state_change: Vec<TriggerFunction>,
-    // finalize_event
-    queued: bool,
+pub struct Machine<S = String, E = String> {
+    states: IndexMap<String, State<S, E>>,
+    initial: Option<String>,
+    models: Vec<ModelRef>,
+    pub(crate) events: IndexMap<String, Event<S, E>>,
+    wildcard_transitions: Vec<TransitionParameters<S, E>>,
+    registry: CallbackRegistry<S, E>,
+    pub prepare_event: Vec<TriggerFunction<S, E>>,
+    pub before_state_change: Vec<TriggerFunction<S, E>>,
+    pub after_state_change: Vec<TriggerFunction<S, E>>,
+    pub finalize_event: Vec<TriggerFunction<S, E>>,
+    pub on_exception: Vec<TriggerFunction<S, E>>,
+    pub(crate) queued: bool,
+    transition_queue: TransitionQueue<S, E>,
     pub(crate) send_event: bool,
-    auto_transitions: bool,
-    ignore_invalid_triggers: bool,
+    pub(crate) auto_transitions: bool,
+    pub(crate) ignore_invalid_triggers: bool,
     pub name: String,
     pub(crate) model_attribute: String,
 }
 
 ///   separates callback type from state/transition name
-#[allow(dead_code)]
 const SEPARATOR: char = '_';
 ///    will be expanded to ALL states
-#[allow(dead_code)]
-const WILDCARD_ALL: char = '*';
+pub(crate) const WILDCARD_ALL: char = '*';
 ///   will be expanded to source state
-#[allow(dead_code)]
-const WILDCARD_SAME: char = '=';
+pub(crate) const WILDCARD_SAME: char = '=';
 // state_cls = State
 // transition_cls = Transition
 // event_cls = Event
 
-impl Machine {
+impl<S: StateValue, E: EventValue> Machine<S, E> {
+    /// Create a new ``Machine``.
     ///         Args:
     ///             model (object or list): The object(s) whose states we want to manage. If 'self',
     ///                 the current Machine instance will be used the model (i.e., all
@@ -111,26 +178,31 @@ impl Machine {
     ///                 It receives the very same args as normal callbacks.
     ///             finalize_event: A callable called on for each triggered event after transitions have been processed.
     ///                 This is also called when a transition raises an exception.
+    ///             on_exception: A callable called when an event raises an exception. If not set,
+    ///                 the Exception will be raised instead. The error is available as ``EventData.error``
+    ///                 and a fallible callback may re-raise it by returning a clone of it (or return another error).
     ///             **kwargs additional arguments passed to next class in MRO. This can be ignored in most cases.
     /// model_attribute='state'???
     #[allow(dead_code)]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        _model: Option<Model>,                                 /*=self*/
-        _states: &[State],                                     //=None=None
-        _initial: Option<State>,                               /*='initial'*/
-        _transitions: &[TransitionParameters],                 /*=None*/
-        send_event: bool,                                      /*=False*/
-        auto_transitions: bool,                                /*=True*/
-        _ordered_transitions: bool,                            /*=False*/
-        ignore_invalid_triggers: bool,                         /*=None*/
-        possible_before_state_change: Option<TriggerFunction>, /*=None*/
-        possible_after_state_change: Option<TriggerFunction>,  /*=None*/
-        name: Option<String>,                                  /*=None*/
-        queued: bool,                                          /*=False*/
-        _prepare_event: TriggerFunction,                       /*=None*/
-        _finalize_event: TriggerFunction,
-        model_attribute: Option<String>, /*='state'*/ /*,kwargs*/
-    ) -> Self {
+        model: Option<ModelRef>,                                     /*=self*/
+        states: Vec<State<S, E>>,                                    //=None=None
+        initial: Option<State<S, E>>,                                /*='initial'*/
+        transitions: Vec<TransitionParameters<S, E>>,                /*=None*/
+        send_event: bool,                                            /*=False*/
+        auto_transitions: bool,                                      /*=True*/
+        ordered_transitions: bool,                                   /*=False*/
+        ignore_invalid_triggers: bool,                               /*=None*/
+        possible_before_state_change: Option<TriggerFunction<S, E>>, /*=None*/
+        possible_after_state_change: Option<TriggerFunction<S, E>>,  /*=None*/
+        name: Option<String>,                                        /*=None*/
+        queued: bool,                                                /*=False*/
+        possible_prepare_event: Option<TriggerFunction<S, E>>,       /*=None*/
+        possible_finalize_event: Option<TriggerFunction<S, E>>,      /*=None*/
+        model_attribute: Option<String>,                             /*='state'*/
+        possible_on_exception: Option<TriggerFunction<S, E>>,        /*=None*/ /*,kwargs*/
+    ) -> Result<Self> {
         // # calling super in case `Machine` is used as a mix in
         // # all keyword arguments should be consumed by now if this is not the case
         // try:
@@ -140,93 +212,127 @@ impl Machine {
 
         // # initialize protected attributes first
 
-        // self._transition_queue = deque()
-        // self._initial = None
-
-        // self.states = OrderedDict()
-        // lf
-        // let initial
-        // let states
-        let models: Vec<Model> = Vec::new();
+        let transition_queue = TransitionQueue::default();
+        let models: Vec<ModelRef> = Vec::new();
         let name = name.map(|n| n + ": ").unwrap_or_else(|| String::from(""));
 
         let model_attribute = model_attribute.unwrap_or_else(|| String::from("state"));
-
-        // self.models = []
-
-        // if states is not None:
-        // self.add_states(states)
-        //
-        // if initial is not None:
-        // self.initial = initial
-
-        // if transitions is not None:
-        // self.add_transitions(transitions)
-
-        // if ordered_transitions:
-        // self.add_ordered_transitions()
-
-        // if model:
-        // self.add_model(model)
-        // self.events = {}
-        // self.prepare_event = prepare_event
-        let mut before_state_change: Vec<TriggerFunction> = Vec::new();
+        // statically known events are registered upfront and can always be triggered
+        let events = E::event_names()
+            .iter()
+            .map(|name| (name.to_string(), Event::new(name.to_string())))
+            .collect();
+        let mut prepare_event: Vec<TriggerFunction<S, E>> = Vec::new();
+        if let Some(f) = possible_prepare_event {
+            prepare_event.push(f);
+        }
+        let mut before_state_change: Vec<TriggerFunction<S, E>> = Vec::new();
         if let Some(f) = possible_before_state_change {
             before_state_change.push(f);
         }
-        let mut after_state_change: Vec<TriggerFunction> = Vec::new();
+        let mut after_state_change: Vec<TriggerFunction<S, E>> = Vec::new();
         if let Some(f) = possible_after_state_change {
             after_state_change.push(f);
         }
-        // self.finalize_event = finalize_event
-        // self._prepare_event = []
-        // self._finalize_event = []
-        Machine {
+        let mut finalize_event: Vec<TriggerFunction<S, E>> = Vec::new();
+        if let Some(f) = possible_finalize_event {
+            finalize_event.push(f);
+        }
+        let mut on_exception: Vec<TriggerFunction<S, E>> = Vec::new();
+        if let Some(f) = possible_on_exception {
+            on_exception.push(f);
+        }
+        let mut machine = Machine {
+            states: IndexMap::new(),
+            initial: None,
             send_event,
             queued,
+            transition_queue,
             auto_transitions,
             ignore_invalid_triggers,
             name,
             models,
+            events,
+            wildcard_transitions: Vec::new(),
+            registry: CallbackRegistry::default(),
+            prepare_event,
             before_state_change,
             after_state_change,
+            finalize_event,
+            on_exception,
             model_attribute,
+        };
+
+        // callables which are not referred to by name must match send_event
+        for func in machine
+            .prepare_event
+            .iter()
+            .chain(&machine.before_state_change)
+            .chain(&machine.after_state_change)
+            .chain(&machine.finalize_event)
+            .chain(&machine.on_exception)
+        {
+            func.check_shape(&machine.name, send_event)?;
         }
+        machine.add_states(states, Vec::new(), Vec::new(), None)?;
+
+        if let Some(initial) = initial {
+            machine.set_initial(initial)?;
+        }
+
+        machine.add_transitions(transitions)?;
+
+        if ordered_transitions {
+            machine.add_ordered_transitions::<S>(
+                None,
+                None,
+                true,
+                true,
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+            )?;
+        }
+
+        // if no model is passed, the machine acts as its own model
+        let model = model.unwrap_or_else(|| Rc::new(RefCell::new(MachineModel::default())));
+        machine.add_model(model, None)?;
+        Ok(machine)
     }
 
-    /// Register a model with the state machine, initializing triggers and callbacks. """
+    /// Register a model with the state machine, initializing triggers and callbacks.
+    ///         Args:
+    ///             model (Model): The model to attach. Attaching a model twice has no effect.
+    ///             initial (str or Enum): The state the model starts in. Defaults to the initial state
+    ///                 of the machine.
     #[allow(dead_code)]
-    pub fn add_model(&mut self, _model: &Model, _initial: Option<State> /*=None*/) {
-        //models = listify(model)
-        // if initial is None:
-        // if self.initial is None:
-        // raise ValueError("No initial state configured for machine, must specify when adding model.")
-        // else:
-        // initial = self.initial
-        //
-        // for mod in models:
-        // mod = self if mod == 'self' else mod
-        // if mod not in self.models:
-        // self._checked_assignment(mod, 'trigger', partial(self._get_trigger, mod))
-        //
-        // for trigger in self.events:
-        // self._add_trigger_to_model(trigger, mod)
-        //
-        // for state in self.states.values():
-        // self._add_model_to_state(state, mod)
-        //
-        // self.set_state(initial, model=mod)
-        // self.models.append(mod)
-        todo!()
+    pub fn add_model(&mut self, model: ModelRef, initial: Option<S> /*=None*/) -> Result<()> {
+        let initial = match initial {
+            Some(initial) => initial.as_ref().to_string(),
+            None => self.initial.clone().ok_or(Error::InitialStateError)?,
+        };
+
+        if !self.models.iter().any(|m| Rc::ptr_eq(m, &model)) {
+            // for state in self.states.values():
+            // self._add_model_to_state(state, mod)
+            self._set_state(&initial, Some(&model))?;
+            self.models.push(model);
+        }
+        Ok(())
     }
-    /// Remove a model from the state machine. The model will still contain all previously added triggers
-    ///         and callbacks, but will not receive updates when states or transitions are added to the Machine. """
+
+    /// Remove a model from the state machine. The model will keep its current state, but will not
+    ///         receive updates when the state of all models is set.
     #[allow(dead_code)]
-    pub fn remove_model(&mut self, _model: &Model) {
-        // models = listify(model)
-        // for mod in models:
-        // self.models.remove(mod)
-        todo!()
+    pub fn remove_model(&mut self, model: &ModelRef) {
+        self.models.retain(|m| !Rc::ptr_eq(m, model));
+    }
+
+    /// List of models attached to the machine.
+    pub fn models(&self) -> &[ModelRef] {
+        &self.models
     }
 
     // @classmethod
@@ -241,39 +347,112 @@ impl Machine {
     // def _create_state(cls, *args, **kwargs):
     // return cls.state_cls(*args, **kwargs)
 
-    // @property
-    // def initial(self):
-    // """ Return the initial state. """
-    // return self._initial
-
-    // @initial.setter
-    // def initial(self, value):
-    // if isinstance(value, State):
-    // if value.name not in self.states:
-    // self.add_state(value)
-    // else:
-    // _ = self._has_state(value, raise_error=True)
-    // self._initial = value.name
-    // else:
-    // state_name = value.name if isinstance(value, Enum) else value
-    // if state_name not in self.states:
-    // self.add_state(state_name)
-    // self._initial = state_name
+    /// Return the initial state.
+    pub fn initial(&self) -> Option<&str> {
+        self.initial.as_deref()
+    }
 
-    // @property
-    // def has_queue(self):
-    // """ Return boolean indicating if machine has queue or not """
-    // return self._queued
+    /// Set the initial state. A state which has not been registered yet will be added to the machine.
+    pub fn set_initial(&mut self, value: State<S, E>) -> Result<()> {
+        let state_name = value.name().to_string();
+        if !self.states.contains_key(&state_name) {
+            self.add_state(vec![value], Vec::new(), Vec::new(), None)?;
+        }
+        self.initial = Some(state_name);
+        Ok(())
+    }
 
-    // @property
-    // def model(self):
-    // """ List of models attached to the machine. For backwards compatibility, the property will
-    //         return the model instance itself instead of the underlying list  if there is only one attached
-    //         to the machine.
-    //         """
-    // if len(self.models) == 1:
-    // return self.models[0]
-    // return self.models
+    /// Return boolean indicating if machine has queue or not
+    pub fn has_queue(&self) -> bool {
+        self.queued
+    }
+
+    /// The registry used to resolve callbacks and conditions referred to by name.
+    pub fn registry(&self) -> &CallbackRegistry<S, E> {
+        &self.registry
+    }
+
+    /// Register a callback which states and transitions can refer to by name.
+    ///         Args:
+    ///             name (str): The name used to refer to the callback.
+    ///             func (TriggerFunction): The callback.
+    ///         Notes:
+    ///             Callbacks named after a dynamic method such as 'on_enter_<state>' or 'before_<trigger>'
+    ///             are added to the according state or transitions as well.
+    pub fn register_callback(&mut self, name: &str, func: TriggerFunction<S, E>) -> Result<()> {
+        func.check_shape(&self.name, self.send_event)?;
+        let known = self.registry.callback(name).is_some();
+        self.registry.register_callback(name, func);
+        if !known {
+            self._bind_dynamic_callback(name);
+        }
+        Ok(())
+    }
+
+    /// Replace the callback registry and bind all callbacks named after dynamic methods. Like
+    ///     ``register_callback``, callbacks which do not match ``send_event`` are rejected.
+    pub(crate) fn set_registry(&mut self, registry: CallbackRegistry<S, E>) -> Result<()> {
+        let names: Vec<String> = registry.callback_names().map(String::from).collect();
+        for name in names.iter() {
+            if let Some(func) = registry.callback(name) {
+                func.check_shape(&self.name, self.send_event)?;
+            }
+        }
+        self.registry = registry;
+        for name in names {
+            self._bind_dynamic_callback(&name);
+        }
+        Ok(())
+    }
+
+    /// Add a newly registered callback to the state or event its name refers to.
+    fn _bind_dynamic_callback(&mut self, name: &str) {
+        let (callback_type, target) = match Self::_identify_callback(name) {
+            Some(identified) => identified,
+            None => return,
+        };
+        if State::<S, E>::DYNAMIC_METHODS.contains(&callback_type) {
+            if let Some(state) = self.states.get_mut(target) {
+                Self::_add_dynamic_state_callback(state, callback_type, name);
+            }
+        } else if let Some(event) = self.events.get_mut(target) {
+            if let Ok(trigger) = TransitionTriggerType::from_str(callback_type) {
+                event.add_named_callback(trigger, name);
+            }
+        }
+    }
+
+    /// Add the callback ``name`` to ``state`` unless it is already mentioned in its callbacks.
+    fn _add_dynamic_state_callback(state: &mut State<S, E>, callback_type: &str, name: &str) {
+        if state.has_callback(name) {
+            return;
+        }
+        if let Ok(trigger) = StateTriggerType::from_str(&callback_type[3..]) {
+            if let Ok(callback) =
+                StateTrigger::from_func_and_type_name(trigger, TriggerFunction::named(name))
+            {
+                state.add_callback(callback);
+            }
+        }
+    }
+
+    /// Register a condition which transitions can refer to by name.
+    ///         Args:
+    ///             name (str): The name used to refer to the condition.
+    ///             func (ConditionFunction): The condition.
+    pub fn register_condition(&mut self, name: &str, func: ConditionFunction<S, E>) -> Result<()> {
+        func.check_shape(&self.name, self.send_event)?;
+        self.registry.register_condition(name, func);
+        Ok(())
+    }
+
+    /// The model attached to the machine, if there is exactly one.
+    pub fn model(&self) -> Option<&ModelRef> {
+        match self.models.as_slice() {
+            [model] => Some(model),
+            _ => None,
+        }
+    }
 
     // @property
     // def before_state_change(self):
@@ -297,84 +476,108 @@ impl Machine {
     // def after_state_change(self, value):
     // self._after_state_change = listify(value)
 
-    // @property
-    // def prepare_event(self):
-    // """Callbacks executed when an event is triggered."""
-    // return self._prepare_event
-
-    // # this should make sure that prepare_event is always a list
-    // @prepare_event.setter
-    // def prepare_event(self, value):
-    // self._prepare_event = listify(value)
-
-    // @property
-    // def finalize_event(self):
-    // """Callbacks will be executed after all transitions callbacks have been executed.
-    //         Callbacks mentioned here will also be called if a transition or condition check raised an error."""
-    // return self._finalize_event
-
-    // # this should make sure that finalize_event is always a list
-    // @finalize_event.setter
-    // def finalize_event(self, value):
-    // self._finalize_event = listify(value)
+    /// Return the State instance of the passed state.
+    #[allow(dead_code)]
+    pub fn get_state<T: Into<S>>(&self, state: T) -> Result<&State<S, E>> {
+        self._get_state(state.into().as_ref())
+    }
 
     /// Return the State instance with the passed name.
-    #[allow(dead_code)]
-    pub fn get_state(&self, _state: &State) -> &State {
-        // if isinstance(state, Enum):
-        // state = state.name
-        // if state not in self.states:
-        // raise ValueError("State '%s ' is not a registered state." % state)
-        // return self.states[state]
-        todo!()
+    pub(crate) fn _get_state(&self, state: &str) -> Result<&State<S, E>> {
+        self.states
+            .get(state)
+            .ok_or_else(|| Error::RegisteredStateError(state.to_string()))
+    }
+
+    /// Return all registered states in the order they have been added.
+    pub fn states(&self) -> impl Iterator<Item = &State<S, E>> {
+        self.states.values()
     }
 
     // # In theory this function could be static. This however causes some issues related to inheritance and
     // # pickling down the chain.
-    // Check whether the current state matches the named state. This function is not called directly
+    /// Check whether the current state matches the named state. This function is not called directly
     ///             but assigned as partials to model instances (e.g. is_A -> partial(_is_state, 'A', model)).
     ///         Args:
-    ///             state (str): name of the checked state
+    ///             state (str or Enum): name of the checked state
     ///             model: model to be checked
     ///         Returns:
     ///             bool: Whether the model's current state is state.
-    #[allow(dead_code)]
-    pub fn is_state(&self, _state: &State, _model: &Model) {
-        // return getattr(model, self.model_attribute) == state
-        todo!()
+    pub fn is_state<T: Into<S>>(&self, state: T, model: &ModelRef) -> bool {
+        self._is_state(state.into().as_ref(), model)
     }
 
-    #[allow(dead_code)]
-    pub fn get_model_state(&self, _model: &Model) {
-        // return self.get_state(getattr(model, self.model_attribute))
-        todo!()
+    fn _is_state(&self, state: &str, model: &ModelRef) -> bool {
+        model.borrow().get_state(&self.model_attribute).as_deref() == Some(state)
     }
 
-    ///             Set the current state.
+    /// Return all models attached to the machine which are currently in the named state.
+    ///         Args:
+    ///             state (str or Enum): name of the checked state
+    ///         Returns:
+    ///             list: The models in the order they have been added.
+    pub fn models_in_state<T: Into<S>>(&self, state: T) -> Vec<&ModelRef> {
+        let state = state.into();
+        self.models
+            .iter()
+            .filter(|model| self._is_state(state.as_ref(), model))
+            .collect()
+    }
+
+    /// Return the State instance the passed model is currently in.
+    pub fn get_model_state(&self, model: &ModelRef) -> Result<&State<S, E>> {
+        let state = model
+            .borrow()
+            .get_state(&self.model_attribute)
+            .ok_or_else(|| {
+                Error::MachineAttributeError(
+                    self.model_attribute.clone(),
+                    self.name.trim_end_matches(": ").to_string(),
+                )
+            })?;
+        self._get_state(&state)
+    }
+
+    /// Set the current state.
     ///         Args:
     ///             state (str or Enum or State): value of state to be set
     ///             model (optional[object]): targeted model; if not set, all models will be set to 'state'
     #[allow(dead_code)]
-    pub fn set_state(&mut self, _state: &State, _model: Option<&Model> /*=None*/) {
-        // if not isinstance(state, State):
-        // state = self.get_state(state)
-        // models = self.models if model is None else listify(model)
-        //
-        // for mod in models:
-        // setattr(mod, self.model_attribute, state.value)
-        todo!()
+    pub fn set_state<T: Into<S>>(
+        &self,
+        state: T,
+        model: Option<&ModelRef>, /*=None*/
+    ) -> Result<()> {
+        self._set_state(state.into().as_ref(), model)
+    }
+
+    pub(crate) fn _set_state(&self, state: &str, model: Option<&ModelRef>) -> Result<()> {
+        let state = self._get_state(state)?;
+        match model {
+            Some(model) => model
+                .borrow_mut()
+                .set_state(&self.model_attribute, state.name().to_string()),
+            None => {
+                for model in self.models.iter() {
+                    model
+                        .borrow_mut()
+                        .set_state(&self.model_attribute, state.name().to_string());
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Alias for add_states.
     #[allow(dead_code)]
     pub fn add_state(
         &mut self,
-        states: Vec<&State>,
-        on_enter: Vec<TriggerFunction>,
-        on_exit: Vec<TriggerFunction>,
-        ignore_invalid_triggers: bool,
+        states: Vec<State<S, E>>,
+        on_enter: Vec<TriggerFunction<S, E>>,
+        on_exit: Vec<TriggerFunction<S, E>>,
+        ignore_invalid_triggers: Option<bool>,
         //**kwargs,
-    ) {
+    ) -> Result<()> {
         self.add_states(
             states,
             on_enter,
@@ -405,52 +608,88 @@ impl Machine {
     #[allow(dead_code)]
     pub fn add_states(
         &mut self,
-        _states: Vec<&State>,
-        _on_enter: Vec<TriggerFunction>, /*=None*/
-        _on_exit: Vec<TriggerFunction>,  /*=None*/
-        _ignore_invalid_triggers: bool,  /*=None*/
-                                         //**kwargs,
-    ) {
-
-        // ignore = ignore_invalid_triggers
-        // if ignore is None:
-        // ignore = self.ignore_invalid_triggers
-
-        // states = listify(states)
-
-        // for state in states:
-        // if isinstance(state, (string_types, Enum)):
-        // state = self._create_state(
-        // state, on_enter=on_enter, on_exit=on_exit,
-        // ignore_invalid_triggers=ignore, **kwargs)
-        // elif isinstance(state, dict):
-        // if 'ignore_invalid_triggers' not in state:
-        // state['ignore_invalid_triggers'] = ignore
-        // state = self._create_state(**state)
-        // self.states[state.name] = state
-        // for model in self.models:
-        // self._add_model_to_state(state, model)
-        // if self.auto_transitions:
-        // for a_state in self.states.keys():
-        // # add all states as sources to auto transitions 'to_<state>' with dest <state>
-        // if a_state == state.name:
-        // if self.model_attribute == 'state':
-        // method_name = 'to_%s' % a_state
-        // else:
-        // method_name = 'to_%s_%s' % (self.model_attribute, a_state)
-        // self.add_transition('to_%s' % a_state, self.WILDCARD_ALL, a_state,
-        // prepare=partial(_warning_wrapper_to, 'to_%s' % a_state))
-        // self.add_transition(method_name, self.WILDCARD_ALL, a_state)
+        states: Vec<State<S, E>>,
+        on_enter: Vec<TriggerFunction<S, E>>, /*=None*/
+        on_exit: Vec<TriggerFunction<S, E>>,  /*=None*/
+        ignore_invalid_triggers: Option<bool>, /*=None*/
+                                              //**kwargs,
+    ) -> Result<()> {
+        // reject mismatching callbacks before any state is added
+        for func in on_enter.iter().chain(on_exit.iter()) {
+            func.check_shape(&self.name, self.send_event)?;
+        }
+        for state in states.iter() {
+            state.check_shape(&self.name, self.send_event)?;
+        }
+        for mut state in states {
+            for func in on_enter.iter() {
+                state.add_callback(StateTrigger::EnterTrigger(func.clone()));
+            }
+            for func in on_exit.iter() {
+                state.add_callback(StateTrigger::ExitTrigger(func.clone()));
+            }
+            // states without an explicit setting keep deferring to the machine
+            if state.ignore_invalid_triggers.is_none() {
+                state.ignore_invalid_triggers = ignore_invalid_triggers;
+            }
+            // Add dynamic method callbacks (enter/exit) if they have been registered
+            // except if they are already mentioned in 'on_enter/exit' of the defined state
+            for callback in State::<S, E>::DYNAMIC_METHODS {
+                let method = format!("{}{}{}", callback, SEPARATOR, state.name());
+                if self.registry.callback(&method).is_some() {
+                    Self::_add_dynamic_state_callback(&mut state, callback, &method);
+                }
+            }
+            let name = state.name().to_string();
+            self.states.insert(name.clone(), state);
+            // for model in self.models:
+            // self._add_model_to_state(state, model)
+            // states added later also receive the transitions defined for all states
+            for trans in self.wildcard_transitions.clone() {
+                self._add_transition(
+                    &trans.trigger,
+                    &[name.as_str()],
+                    trans.dest.as_deref(),
+                    trans.conditions,
+                    trans.unless,
+                    trans.before,
+                    trans.after,
+                    trans.prepare,
+                )?;
+            }
+            let auto_transition = self.auto_transition_name(&name);
+            // with statically known events, only auto transitions named by an event are added
+            if self.auto_transitions && Self::_is_event_name(&auto_transition) {
+                // add all states as sources to auto transitions 'to_<state>' with dest <state>;
+                // auto transitions to previously added states are wildcard transitions as well
+                // and have just been extended by the loop above
+                let wildcard = WILDCARD_ALL.to_string();
+                self._add_transition(
+                    &auto_transition,
+                    &[wildcard.as_str()],
+                    Some(&name),
+                    PotentialConditions::default(),
+                    PotentialConditions::default(),
+                    PotentialTriggers::default(),
+                    PotentialTriggers::default(),
+                    PotentialTriggers::default(),
+                )?;
+            }
+        }
+        Ok(())
+    }
 
-        // # add auto transition with source <state> to <a_state>
-        // else:
-        // if self.model_attribute == 'state':
-        // method_name = 'to_%s' % a_state
-        // else:
-        // method_name = 'to_%s_%s' % (self.model_attribute, a_state)
-        // self.add_transition('to_%s' % a_state, state.name, a_state,
-        // prepare=partial(_warning_wrapper_to, 'to_%s' % a_state))
-        // self.add_transition(method_name, state.name, a_state)
+    /// The name of the auto transition trigger leading to ``state``. When ``model_attribute`` has been
+    ///     customized, the trigger is called 'to_<model_attribute>_<state_name>' instead of 'to_<state_name>'.
+    pub(crate) fn auto_transition_name(&self, state: &str) -> String {
+        if self.model_attribute == "state" {
+            format!("to{}{}", SEPARATOR, state)
+        } else {
+            format!(
+                "to{}{}{}{}",
+                SEPARATOR, self.model_attribute, SEPARATOR, state
+            )
+        }
     }
 
     // def _add_model_to_state(self, state, model):
@@ -465,14 +704,6 @@ impl Machine {
     // method_name = 'is_%s_%s' % (self.model_attribute, state.name)
     // self._checked_assignment(model, 'is_%s' % state.name, partial(_warning_wrapper_is, method_name, func))
     // self._checked_assignment(model, method_name, func)
-    //
-    // # Add dynamic method callbacks (enter/exit) if there are existing bound methods in the model
-    // # except if they are already mentioned in 'on_enter/exit' of the defined state
-    // for callback in self.state_cls.dynamic_methods:
-    // method = "{0}_{1}".format(callback, state.name)
-    // if hasattr(model, method) and inspect.ismethod(getattr(model, method)) and \
-    // method not in getattr(state, callback):
-    // state.add_callback(callback[3:], method)
 
     // def _checked_assignment(self, model, name, func):
     // if hasattr(model, name):
@@ -492,32 +723,119 @@ impl Machine {
     ///             **kwargs: Arbitrary keyword arguments which is passed to the triggered event.
     ///         Returns:
     ///             bool: True if a transitions has been conducted or the trigger event has been queued.
-    fn _get_trigger(&self, _model: &Model, _trigger_name: &str) {
-        //, *args, **kwargs
-        // try:
-        // event = self.events[trigger_name]
-        // except KeyError:
-        // state = self.get_model_state(model)
-        // ignore = state.ignore_invalid_triggers if state.ignore_invalid_triggers is not None \
-        // else self.ignore_invalid_triggers
-        // if not ignore:
-        // raise AttributeError("Do not know event named '%s'." % trigger_name)
-        // return False
-        // return event.trigger(model, *args, **kwargs)
-        todo!()
+    fn _get_trigger(&self, model: &ModelRef, trigger_name: &str, args: EventArgs) -> Result<bool> {
+        match self.events.get(trigger_name) {
+            Some(event) => event.trigger(self, model, args),
+            None => {
+                let state = self.get_model_state(model)?;
+                let ignore = state
+                    .ignore_invalid_triggers()
+                    .unwrap_or(self.ignore_invalid_triggers);
+                if !ignore {
+                    return Err(Error::UnknownEventError(trigger_name.to_string()));
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    /// Return the Event registered under the passed trigger name.
+    pub fn get_event(&self, trigger_name: &str) -> Result<&Event<S, E>> {
+        self.events
+            .get(trigger_name)
+            .ok_or_else(|| Error::UnknownEventError(trigger_name.to_string()))
+    }
+
+    /// Trigger an event on the passed model.
+    ///         Args:
+    ///             model (object): Model whose state should change.
+    ///             trigger (str or EventValue): Name of the trigger to be called or a value such as
+    ///                 an enumeration variant. The value is made available via ``EventData::event_value``.
+    ///         Returns:
+    ///             bool: True if a transitions has been conducted or the trigger event has been queued.
+    pub fn trigger<T: Into<E>>(&self, model: &ModelRef, trigger: T) -> Result<bool> {
+        self.trigger_with_args(model, trigger, EventArgs::default())
+    }
+
+    /// Trigger an event on the passed model by name and pass arguments along to all callbacks.
+    ///         Args:
+    ///             model (object): Model whose state should change.
+    ///             trigger (str or EventValue): Name of the trigger to be called or a value.
+    ///             args (EventArgs): Positional and keyword arguments made available to all
+    ///                 callbacks and conditions via ``EventData``.
+    ///         Returns:
+    ///             bool: True if a transitions has been conducted or the trigger event has been queued.
+    pub fn trigger_with_args<T: Into<E>>(
+        &self,
+        model: &ModelRef,
+        trigger: T,
+        args: EventArgs,
+    ) -> Result<bool> {
+        let trigger = trigger.into();
+        let trigger_name = trigger.as_ref().to_string();
+        self._get_trigger(model, &trigger_name, args.with_event_value(trigger))
+    }
+
+    /// Check whether an event could be triggered on the passed model in its current state.
+    ///         Machine preparation callbacks as well as the prepare callbacks of every candidate
+    ///         transition are executed and conditions are evaluated, but no state is changed.
+    ///         Args:
+    ///             model (object): Model whose state would change.
+    ///             trigger (str or EventValue): Name of the trigger to be checked or a value.
+    ///         Returns:
+    ///             bool: True if at least one transition of the trigger would pass its conditions.
+    pub fn may_trigger<T: Into<E>>(&self, model: &ModelRef, trigger: T) -> Result<bool> {
+        self.may_trigger_with_args(model, trigger, EventArgs::default())
+    }
+
+    /// Check whether an event could be triggered on the passed model with the passed arguments.
+    ///         See ``may_trigger``.
+    pub fn may_trigger_with_args<T: Into<E>>(
+        &self,
+        model: &ModelRef,
+        trigger: T,
+        args: EventArgs,
+    ) -> Result<bool> {
+        let trigger = trigger.into();
+        let event = match self.events.get(trigger.as_ref()) {
+            Some(event) => event,
+            None => return Ok(false),
+        };
+        let args = args.with_event_value(trigger);
+        let state = self.get_model_state(model)?;
+        let mut event_data = EventData::new(state, event, self, model, args);
+        for transition in event.transitions(state.name()) {
+            if let Some(dest) = transition.dest() {
+                if !self._has_state(dest, false)? {
+                    continue;
+                }
+            }
+            event_data.transition = Some(transition);
+            let prepare: Vec<_> = self.prepare_event.iter().collect();
+            self.callbacks(prepare.as_slice(), &event_data)?;
+            if transition.may_execute(&event_data)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
     }
 
     /// Collects all triggers FROM certain states.
     ///         Args:
-    ///             *args: Tuple of source states.
+    ///             states: Source states.
     ///         Returns:
     ///             list of transition/trigger names.
-    #[allow(dead_code)]
-    pub fn get_triggers(&self) {
-        //, *args
-        // states = set(args)
-        // return [t for (t, ev) in self.events.items() if any(state in ev.transitions for state in states)]
-        todo!()
+    pub fn get_triggers<T: Clone + Into<S>>(&self, states: &[T]) -> Vec<&str> {
+        let states: Vec<S> = states.iter().cloned().map(Into::into).collect();
+        self.events
+            .iter()
+            .filter(|(_, ev)| {
+                states
+                    .iter()
+                    .any(|state| !ev.transitions(state.as_ref()).is_empty())
+            })
+            .map(|(t, _)| t.as_str())
+            .collect()
     }
 
     /// Create a new Transition instance and add it to the internal list.
@@ -526,15 +844,19 @@ impl Machine {
     ///                 transition. This will be attached to the currently specified
     ///                 model (e.g., passing trigger='advance' will create a new
     ///                 advance() method in the model that triggers the transition.)
-    ///             source(str or list): The name of the source state--i.e., the state we
+    ///             source(str, Enum or list): The source state--i.e., the state we
     ///                 are transitioning away from. This can be a single state, a
-    ///                 list of states or an asterisk for all states.
-    ///             dest (str): The name of the destination State--i.e., the state
+    ///                 list of states or an asterisk for all states (including states added later).
+    ///             dest (str or Enum): The destination State--i.e., the state
     ///                 we are transitioning into. This can be a single state or an
     ///                 equal sign to specify that the transition should be reflexive
     ///                 so that the destination will be the same as the source for
     ///                 every given source. If dest is None, this transition will be
     ///                 an internal transition (exit/enter callbacks won't be processed).
+    ///                 Machines with enumerated states use ``TransitionParameters::wildcard`` and
+    ///                 ``TransitionParameters::reflexive`` for the asterisk and the equal sign.
+    ///         Raises:
+    ///             UnknownStateError: source or dest has not been added to the machine.
     ///             conditions (str or list): Condition(s) that must pass in order
     ///                 for the transition to take place. Either a list providing the
     ///                 name of a callable, or a list of callables. For the transition
@@ -548,68 +870,162 @@ impl Machine {
     ///             **kwargs: Additional arguments which can be passed to the created transition.
     ///                 This is useful if you plan to extend Machine.Transition and require more parameters.
     #[allow(dead_code)]
-    pub fn add_transition(
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_transition<K: Into<E>, T: Clone + Into<S>>(
         &mut self,
-        _trigger: TriggerFunction,
-        _source: &[&str],
-        _dest: &str,
-        _conditions: &[ConditionFunction], /*=None*/
-        _unless: &[&ConditionFunction],    /*=None*/
-        _before: &[&TriggerFunction],      /*None*/
-        _after: &[&TriggerFunction],       /*=None*/
-        _prepare: &[&TriggerFunction],     /*=None*/
-    ) {
-        //, **kwargs
-        // if trigger == self.model_attribute:
-        // raise ValueError("Trigger name cannot be same as model attribute name.")
-        // if trigger not in self.events:
-        // self.events[trigger] = self._create_event(trigger, self)
+        trigger: K,
+        source: &[T],
+        dest: Option<T>,
+        conditions: PotentialConditions<S, E>, /*=None*/
+        unless: PotentialConditions<S, E>,     /*=None*/
+        before: PotentialTriggers<S, E>,       /*None*/
+        after: PotentialTriggers<S, E>,        /*=None*/
+        prepare: PotentialTriggers<S, E>,      /*=None*/
+    ) -> Result<()> {
+        let source: Vec<S> = source.iter().cloned().map(Into::into).collect();
+        let source: Vec<&str> = source.iter().map(|s| s.as_ref()).collect();
+        let dest: Option<S> = dest.map(Into::into);
+        self._add_transition(
+            trigger.into().as_ref(),
+            &source,
+            dest.as_ref().map(|d| d.as_ref()),
+            conditions,
+            unless,
+            before,
+            after,
+            prepare,
+        )
+    }
+
+    /// Add a transition whose event and states are referred to by name, including the wildcards
+    ///     '*' and '='.
+    #[allow(clippy::too_many_arguments)]
+    fn _add_transition(
+        &mut self,
+        trigger: &str,
+        source: &[&str],
+        dest: Option<&str>,
+        conditions: PotentialConditions<S, E>,
+        unless: PotentialConditions<S, E>,
+        before: PotentialTriggers<S, E>,
+        after: PotentialTriggers<S, E>,
+        prepare: PotentialTriggers<S, E>,
+    ) -> Result<()> {
+        if trigger == self.model_attribute {
+            return Err(Error::TriggerNameError(trigger.to_string()));
+        }
+        for conds in [&conditions, &unless] {
+            conds.check_shape(&self.name, self.send_event)?;
+        }
+        for funcs in [&before, &after, &prepare] {
+            funcs.check_shape(&self.name, self.send_event)?;
+        }
+        if !Self::_is_event_name(trigger) {
+            return Err(Error::UnknownEventError(trigger.to_string()));
+        }
+        let wildcard = WILDCARD_ALL.to_string();
+        if source != [wildcard.as_str()] {
+            for state in source {
+                self._has_state(state, true)?;
+            }
+        }
+        if let Some(dest) = dest.filter(|d| *d != WILDCARD_SAME.to_string()) {
+            self._has_state(dest, true)?;
+        }
+        // transitions equal to the auto transition 'to_<dest>' are recreated when a machine is
+        // loaded from its definition and therefore not exported
+        let auto = self.auto_transitions
+            && dest.is_some_and(|d| trigger == self.auto_transition_name(d))
+            && conditions.is_empty()
+            && unless.is_empty()
+            && before.is_empty()
+            && after.is_empty()
+            && prepare.is_empty();
+        let registry = &self.registry;
+        let event = self
+            .events
+            .entry(trigger.to_string())
+            .or_insert_with(|| Event::new(trigger.to_string()));
         // for model in self.models:
         // self._add_trigger_to_model(trigger, model)
-        //
-        // if source == self.WILDCARD_ALL:
-        // source = list(self.states.keys())
-        // else:
-        // # states are checked lazily which means we will only raise exceptions when the passed state
-        // # is a State object because of potential confusion (see issue #155 for more details)
-        // source = [s.name if isinstance(s, State) and self._has_state(s, raise_error=True) or hasattr(s, 'name') else
-        // s for s in listify(source)]
-        //
-        // for state in source:
-        // if dest == self.WILDCARD_SAME:
-        // _dest = state
-        // elif dest is not None:
-        // if isinstance(dest, State):
-        // _ = self._has_state(dest, raise_error=True)
-        // _dest = dest.name if hasattr(dest, 'name') else dest
-        // else:
-        // _dest = None
-        // _trans = self._create_transition(state, _dest, conditions, unless, before,
-        // after, prepare, **kwargs)
-        // self.events[trigger].add_transition(_trans)
-        todo!()
+
+        let all_states: Vec<&str>;
+        let source = if source == [wildcard.as_str()] {
+            // remember the definition so that states added later receive it as well
+            self.wildcard_transitions.push(TransitionParameters {
+                trigger: trigger.to_string(),
+                source: vec![wildcard.clone()],
+                dest: dest.map(|d| d.to_string()),
+                conditions: conditions.clone(),
+                unless: unless.clone(),
+                before: before.clone(),
+                after: after.clone(),
+                prepare: prepare.clone(),
+            });
+            all_states = self.states.keys().map(|s| s.as_str()).collect();
+            all_states.as_slice()
+        } else {
+            source
+        };
+
+        for state in source {
+            let dest = match dest {
+                Some(dest) if dest == WILDCARD_SAME.to_string() => Some(*state),
+                dest => dest,
+            };
+            let mut transition = Transition::new(
+                state.to_string(),
+                dest.map(|d| d.to_string()),
+                conditions.clone(),
+                unless.clone(),
+                before.clone(),
+                after.clone(),
+                prepare.clone(),
+            );
+            transition.auto = auto;
+            // add dynamic method callbacks (before/after/prepare) if they have been registered
+            for callback in Transition::<S, E>::DYNAMIC_METHODS {
+                let method = format!("{}{}{}", callback, SEPARATOR, trigger);
+                if registry.callback(&method).is_some() && !transition.has_callback(&method) {
+                    if let Ok(trigger_type) = TransitionTriggerType::from_str(callback) {
+                        transition.add_callback(trigger_type, TriggerFunction::named(&method));
+                    }
+                }
+            }
+            event.add_transition(transition);
+        }
+        Ok(())
     }
 
     /// Add several transitions.
     ///         Args:
     ///             transitions (list): A list of transitions.
     #[allow(dead_code)]
-    pub fn add_transitions(&mut self, _transitions: &[&Transition]) { //Function
-                                                                     // for trans in listify(transitions):
-                                                                     // if isinstance(trans, list):
-                                                                     // self.add_transition(*trans)
-                                                                     // else:
-                                                                     // self.add_transition(**trans)
+    pub fn add_transitions(&mut self, transitions: Vec<TransitionParameters<S, E>>) -> Result<()> {
+        for trans in transitions {
+            let source: Vec<&str> = trans.source.iter().map(|s| s.as_str()).collect();
+            self._add_transition(
+                &trans.trigger,
+                &source,
+                trans.dest.as_deref(),
+                trans.conditions,
+                trans.unless,
+                trans.before,
+                trans.after,
+                trans.prepare,
+            )?;
+        }
+        Ok(())
     }
 
     /// Add a set of transitions that move linearly from state to state.
     ///         Args:
-    ///             states (list): A list of state names defining the order of the
+    ///             states (list): A list of states or state names defining the order of the
     ///                 transitions. E.g., ['A', 'B', 'C'] will generate transitions
     ///                 for A --> B, B --> C, and C --> A (if loop is True). If states
     ///                 is None, all states in the current instance will be used.
-    ///             trigger (str): The name of the trigger method that advances to
-    ///                 the next state in the sequence.
+    ///             trigger (str or EventValue): The trigger that advances to the next state in
+    ///                 the sequence. Defaults to 'next_state'.
     ///             loop (boolean): Whether or not to add a transition from the last
     ///                 state to the first state.
     ///             loop_includes_initial (boolean): If no initial state was defined in
@@ -630,61 +1046,82 @@ impl Machine {
     ///             **kwargs: Additional arguments which can be passed to the created transition.
     ///                 This is useful if you plan to extend Machine.Transition and require more parameters.
     #[allow(dead_code)]
-    pub fn add_ordered_transitions(
-        self,
-        _states: &[&str],                  /*=None*/
-        _trigger: Option<&str>,            /*='next_state'*/
-        _transition_loop: bool,            /*=True*/
-        _loop_includes_initial: bool,      /*=True*/
-        _conditions: &[ConditionFunction], /*=None*/
-        _unless: &[&ConditionFunction],    /*=None*/
-        _before: &[&TriggerFunction],      /*=None*/
-        _after: &[&TriggerFunction],       /*=None*/
-        _prepare: &[&TriggerFunction],     /*=None*/
-    ) {
-        //, **kwargs
-        // if states is None:
-        //     states = list(self.states.keys())  # need to listify for Python3
-        // len_transitions = len(states)
-        // if len_transitions < 2:
-        //     raise ValueError("Can't create ordered transitions on a Machine "
-        // "with fewer than 2 states.")
-        // if not transition_loop:
-        //     len_transitions -= 1
-        // # ensure all args are the proper length
-        // conditions = _prep_ordered_arg(len_transitions, conditions)
-        // unless = _prep_ordered_arg(len_transitions, unless)
-        // before = _prep_ordered_arg(len_transitions, before)
-        // after = _prep_ordered_arg(len_transitions, after)
-        // prepare = _prep_ordered_arg(len_transitions, prepare)
-        // # reorder list so that the initial state is actually the first one
-        // try:
-        //     idx = states.index(self._initial)
-        // states = states[idx:] + states[:idx]
-        // first_in_loop = states[0 if loop_includes_initial else 1]
-        // except ValueError:
-        // # since initial is not part of states it shouldn't be part of the loop either
-        // first_in_loop = states[0]
-        //
-        // for i in range(0, len(states) - 1):
-        //     self.add_transition(trigger, states[i], states[i + 1],
-        //                         conditions=conditions[i],
-        //                         unless=unless[i],
-        //                         before=before[i],
-        //                         after=after[i],
-        //                         prepare=prepare[i],
-        //                         **kwargs)
-        // if loop:
-        //     self.add_transition(trigger, states[-1],
-        // # omit initial if not loop_includes_initial
-        // first_in_loop,
-        // conditions=conditions[-1],
-        // unless=unless[-1],
-        // before=before[-1],
-        // after=after[-1],
-        // prepare=prepare[-1],
-        // **kwargs)
-        todo!()
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_ordered_transitions<T: Clone + Into<S>>(
+        &mut self,
+        states: Option<&[T]>,                       /*=None*/
+        trigger: Option<E>,                         /*='next_state'*/
+        transition_loop: bool,                      /*=True*/
+        loop_includes_initial: bool,                /*=True*/
+        conditions: Vec<PotentialConditions<S, E>>, /*=None*/
+        unless: Vec<PotentialConditions<S, E>>,     /*=None*/
+        before: Vec<PotentialTriggers<S, E>>,       /*=None*/
+        after: Vec<PotentialTriggers<S, E>>,        /*=None*/
+        prepare: Vec<PotentialTriggers<S, E>>,      /*=None*/
+    ) -> Result<()> {
+        let trigger = trigger.as_ref().map_or("next_state", |t| t.as_ref());
+        let mut states: Vec<String> = match states {
+            Some(states) => states
+                .iter()
+                .cloned()
+                .map(|s| s.into().as_ref().to_string())
+                .collect(),
+            None => self.states.keys().cloned().collect(),
+        };
+        let mut len_transitions = states.len();
+        if len_transitions < 2 {
+            return Err(Error::InsufficientStatesError);
+        }
+        if !transition_loop {
+            len_transitions -= 1;
+        }
+        // ensure all args are the proper length
+        let conditions = _prep_ordered_arg(len_transitions, conditions)?;
+        let unless = _prep_ordered_arg(len_transitions, unless)?;
+        let before = _prep_ordered_arg(len_transitions, before)?;
+        let after = _prep_ordered_arg(len_transitions, after)?;
+        let prepare = _prep_ordered_arg(len_transitions, prepare)?;
+        // reorder list so that the initial state is actually the first one
+        let initial_idx = self
+            .initial
+            .as_ref()
+            .and_then(|initial| states.iter().position(|s| s == initial));
+        let first_in_loop = match initial_idx {
+            Some(idx) => {
+                states.rotate_left(idx);
+                states[if loop_includes_initial { 0 } else { 1 }].clone()
+            }
+            // since initial is not part of states it shouldn't be part of the loop either
+            None => states[0].clone(),
+        };
+
+        for i in 0..states.len() - 1 {
+            self._add_transition(
+                trigger,
+                &[states[i].as_str()],
+                Some(&states[i + 1]),
+                conditions[i].clone(),
+                unless[i].clone(),
+                before[i].clone(),
+                after[i].clone(),
+                prepare[i].clone(),
+            )?;
+        }
+        if transition_loop {
+            let last = len_transitions - 1;
+            self._add_transition(
+                trigger,
+                &[states[states.len() - 1].as_str()],
+                // omit initial if not loop_includes_initial
+                Some(&first_in_loop),
+                conditions[last].clone(),
+                unless[last].clone(),
+                before[last].clone(),
+                after[last].clone(),
+                prepare[last].clone(),
+            )?;
+        }
+        Ok(())
     }
 
     /// Return the transitions from the Machine.
@@ -720,58 +1157,83 @@ impl Machine {
 
     /// Removes a transition from the Machine and all models.
     ///         Args:
-    ///             trigger (str): Trigger name of the transition.
-    ///             source (str): Limits removal to transitions from a certain state.
-    ///             dest (str): Limits removal to transitions to a certain state.
-    #[allow(dead_code)]
-    pub fn remove_transition(
-        self,
-        _trigger: &str,
-        _source: Option<&str>, /*="*"*/
-        _dest: Option<&str>,   /*="*"*/
-    ) {
-        // source = listify(source) if source != "*" else source
-        // dest = listify(dest) if dest != "*" else dest
-        // # outer comprehension, keeps events if inner comprehension returns lists with length > 0
-        // tmp = {key: value for key, value in
-        // {k: [t for t in v
-        // # keep entries if source should not be filtered; same for dest.
-        // if (source != "*" and t.source not in source) or (dest != "*" and t.dest not in dest)]
-        // # }.items() takes the result of the inner comprehension and uses it
-        // # for the outer comprehension (see first line of comment)
-        // for k, v in self.events[trigger].transitions.items()}.items()
-        // if len(value) > 0}
-        // # convert dict back to defaultdict in case tmp is not empty
-        // if tmp:
-        // self.events[trigger].transitions = defaultdict(list, **tmp)
-        // # if no transition is left remove the trigger from the machine and all models
-        // else:
-        // for model in self.models:
-        // delattr(model, trigger)
-        // del self.events[trigger]
-        todo!()
+    ///             trigger (str or EventValue): Trigger of the transition.
+    ///             source (str or Enum): Limits removal to transitions from a certain state.
+    ///             dest (str or Enum): Limits removal to transitions to a certain state.
+    pub fn remove_transition<K: Into<E>, T: Into<S>>(
+        &mut self,
+        trigger: K,
+        source: Option<T>, /*="*"*/
+        dest: Option<T>,   /*="*"*/
+    ) -> Result<()> {
+        let trigger = trigger.into();
+        let trigger = trigger.as_ref();
+        let (source, dest): (Option<S>, Option<S>) = (source.map(Into::into), dest.map(Into::into));
+        let wildcard = WILDCARD_ALL.to_string();
+        let source = source
+            .as_ref()
+            .map(|s| s.as_ref())
+            .filter(|s| *s != wildcard);
+        let dest = dest.as_ref().map(|d| d.as_ref()).filter(|d| *d != wildcard);
+        let event = self
+            .events
+            .get_mut(trigger)
+            .ok_or_else(|| Error::UnknownEventError(trigger.to_string()))?;
+        // keep transitions if source should not be filtered; same for dest.
+        event.retain_transitions(|t| {
+            source.is_some_and(|s| t.source() != s) || dest.is_some_and(|d| t.dest() != Some(d))
+        });
+        // if no transition is left remove the trigger from the machine unless it is known statically
+        if event.is_empty() && !E::event_names().contains(&trigger) {
+            self.events.shift_remove(trigger);
+        }
+        // states added later must not receive the removed transitions either
+        self.wildcard_transitions.retain(|t| {
+            t.trigger != trigger
+                || source.is_some_and(|s| !t.source.iter().any(|ts| ts == s))
+                || dest.is_some_and(|d| t.dest.as_deref() != Some(d))
+        });
+        Ok(())
     }
 
     /// Trigger an event on all models assigned to the machine.
     ///         Args:
-    ///             trigger (str): Event name
-    ///             *args (list): List of arguments passed to the event trigger
-    ///             **kwargs (dict): Dictionary of keyword arguments passed to the event trigger
+    ///             trigger (str or EventValue): Event name or value
     ///         Returns:
     ///             bool The truth value of all triggers combined with AND
     #[allow(dead_code)]
-    pub fn dispatch(self, _trigger: TriggerFunction) {
-        //, *args, **kwargs
-        // return all([getattr(model, trigger)(*args, **kwargs) for model in self.models])
-        todo!()
+    pub fn dispatch<T: Into<E>>(&self, trigger: T) -> Result<bool> {
+        self.dispatch_with_args(trigger, EventArgs::default())
+    }
+
+    /// Trigger an event on all models assigned to the machine and pass arguments along.
+    ///         Args:
+    ///             trigger (str or EventValue): Event name or value
+    ///             args (EventArgs): Arguments passed to the event trigger of every model
+    ///         Returns:
+    ///             bool The truth value of all triggers combined with AND
+    pub fn dispatch_with_args<T: Into<E>>(&self, trigger: T, args: EventArgs) -> Result<bool> {
+        let trigger = trigger.into();
+        let trigger_name = trigger.as_ref().to_string();
+        let args = args.with_event_value(trigger);
+        let mut result = true;
+        for model in self.models.iter() {
+            result &= self._get_trigger(model, &trigger_name, args.clone())?;
+        }
+        Ok(result)
     }
 
     /// Triggers a list of callbacks
-    pub(crate) fn callbacks(&self, funcs: &[&TriggerFunction], event_data: &EventData) {
+    pub(crate) fn callbacks(
+        &self,
+        funcs: &[&TriggerFunction<S, E>],
+        event_data: &EventData<'_, S, E>,
+    ) -> Result<()> {
         for func in funcs {
-            self.callback(func, event_data);
+            self.callback(func, event_data)?;
             info!("{}: Executed callback '{}'", self.name, func);
         }
+        Ok(())
     }
 
     /// Trigger a callback function with passed event_data parameters. In case func is a string,
@@ -785,70 +1247,88 @@ impl Machine {
     ///             event_data (EventData): An EventData instance to pass to the
     ///                 callback (if event sending is enabled) or to extract arguments
     ///                 from (if event sending is disabled).
-    pub fn callback(&self, _func: &TriggerFunction, _event_data: &EventData) {
-        // func = self.resolve_callable(func, event_data)
-        // if self.send_event:
-        // func(event_data)
-        // else:
-        // func(*event_data.args, **event_data.kwargs)
-        todo!()
+    pub fn callback(
+        &self,
+        func: &TriggerFunction<S, E>,
+        event_data: &EventData<'_, S, E>,
+    ) -> Result<()> {
+        let resolved = func.resolve_callable(event_data)?;
+        // callables passed directly have been checked when they were added
+        if func.is_named() {
+            resolved.check_shape(&self.name, self.send_event)?;
+        }
+        resolved.execute(event_data)
     }
-    fn _has_state(self, _state: &State, _raise_error: bool /*=False*/) {
-        // found = state in self.states.values()
-        // if not found and raise_error:
-        // msg = 'State %s has not been added to the machine' % (state.name if hasattr(state, 'name') else state)
-        // raise ValueError(msg)
-        // return found
-        todo!()
+    /// Whether events of type ``E`` may be named ``name``; any name is valid for ``String``.
+    fn _is_event_name(name: &str) -> bool {
+        let names = E::event_names();
+        names.is_empty() || names.contains(&name)
     }
 
-    fn _process(&self, _trigger: TriggerFunction) {
-        // # default processing
-        // if not self.has_queue:
-        // if not self._transition_queue:
-        // # if trigger raises an Error, it has to be handled by the Machine.process caller
-        // return trigger()
-        // else:
-        // raise MachineError("Attempt to process events synchronously while transition queue is not empty!")
-        //
-        // # process queued events
-        // self._transition_queue.append(trigger)
-        // # another entry in the queue implies a running transition; skip immediate execution
-        // if len(self._transition_queue) > 1:
-        // return True
-        //
-        // # execute as long as transition queue is not empty
-        // while self._transition_queue:
-        // try:
-        // self._transition_queue[0]()
-        // self._transition_queue.popleft()
-        // except Exception:
-        // # if a transition raises an exception, clear queue and delegate exception handling
-        // self._transition_queue.clear()
-        // raise
-        // return True
-        todo!()
+    fn _has_state(&self, state: &str, raise_error: bool /*=False*/) -> Result<bool> {
+        let found = self.states.contains_key(state);
+        if !found && raise_error {
+            return Err(Error::UnknownStateError(state.to_string()));
+        }
+        Ok(found)
+    }
+
+    pub(crate) fn _process(&self, trigger: QueuedTrigger<S, E>) -> Result<bool> {
+        let queue = &self.transition_queue.0;
+        // default processing
+        if !self.has_queue() {
+            if queue.borrow().is_empty() {
+                // if trigger raises an Error, it has to be handled by the Machine.process caller
+                return trigger(self);
+            }
+            return Err(Error::MachineError(String::from(
+                "Attempt to process events synchronously while transition queue is not empty!",
+            )));
+        }
+
+        // process queued events
+        queue.borrow_mut().push_back(Some(trigger));
+        // another entry in the queue implies a running transition; skip immediate execution
+        if queue.borrow().len() > 1 {
+            return Ok(true);
+        }
+
+        // execute as long as transition queue is not empty
+        loop {
+            let trigger = match queue.borrow_mut().front_mut() {
+                Some(entry) => entry.take(),
+                None => break,
+            };
+            if let Some(trigger) = trigger {
+                if let Err(err) = trigger(self) {
+                    // if a transition raises an exception, clear queue and delegate exception handling
+                    queue.borrow_mut().clear();
+                    return Err(err);
+                }
+            }
+            queue.borrow_mut().pop_front();
+        }
+        Ok(true)
+    }
+
+    /// Identify a dynamic method name such as 'on_enter_<state>' or 'before_<trigger>'.
+    ///         Returns:
+    ///             The callback type (e.g. 'on_enter') and its target (e.g. the state name) or None.
+    fn _identify_callback(name: &str) -> Option<(&'static str, &str)> {
+        // Does the prefix match a known callback?
+        let callback_type = State::<S, E>::DYNAMIC_METHODS
+            .iter()
+            .chain(Transition::<S, E>::DYNAMIC_METHODS)
+            .find(|callback| name.starts_with(*callback))?;
+
+        // Make sure there is actually a target and enforce _ as a separator
+        let target = name[callback_type.len()..].strip_prefix(SEPARATOR)?;
+        if target.is_empty() {
+            return None;
+        }
+        Some((callback_type, target))
     }
 
-    // @classmethod
-    // def _identify_callback(cls, name):
-    // # Does the prefix match a known callback?
-    // for callback in itertools.chain(cls.state_cls.dynamic_methods, cls.transition_cls.dynamic_methods):
-    // if name.startswith(callback):
-    // callback_type = callback
-    // break
-    // else:
-    // return None, None
-    //
-    // # Extract the target by cutting the string after the type and separator
-    // target = name[len(callback_type) + len(cls.SEPARATOR):]
-    //
-    // # Make sure there is actually a target to avoid index error and enforce _ as a separator
-    // if target == '' or name[len(callback_type)] != cls.SEPARATOR:
-    // return None, None
-    //
-    // return callback_type, target
-    //
     // def __getattr__(self, name):
     // # Machine.__dict__ does not contain double underscore variables.
     // # Class variables will be mangled.
@@ -0,0 +1,67 @@
+//! A registry of named callbacks. Transitions and states may refer to callbacks by name, e.g. when
+//! they have been defined in a configuration file, and those names are resolved when an event is
+//! processed.
+
+use crate::core::{ConditionFunction, StateValue, TriggerFunction};
+use crate::event::EventValue;
+use std::collections::HashMap;
+
+/// Maps names to callbacks and conditions.
+///     Attributes:
+///         callbacks (dict): Callbacks which can be used for state and transition callbacks.
+///         conditions (dict): Callables which can be used as conditions or unless conditions.
+#[derive(Debug, Clone)]
+pub struct CallbackRegistry<S = String, E = String> {
+    callbacks: HashMap<String, TriggerFunction<S, E>>,
+    conditions: HashMap<String, ConditionFunction<S, E>>,
+}
+
+impl<S: StateValue, E: EventValue> Default for CallbackRegistry<S, E> {
+    fn default() -> Self {
+        CallbackRegistry::new()
+    }
+}
+
+impl<S: StateValue, E: EventValue> CallbackRegistry<S, E> {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        CallbackRegistry {
+            callbacks: HashMap::new(),
+            conditions: HashMap::new(),
+        }
+    }
+
+    /// Register a callback under the passed name. An already registered callback with the same
+    ///     name is replaced. Unnamed callbacks are named after their registration.
+    ///         Args:
+    ///             name (str): The name used to refer to the callback.
+    ///             func (TriggerFunction): The callback.
+    pub fn register_callback(&mut self, name: &str, func: TriggerFunction<S, E>) {
+        self.callbacks.insert(name.to_string(), func.or_named(name));
+    }
+
+    /// Register a condition under the passed name. An already registered condition with the same
+    ///     name is replaced. Unnamed conditions are named after their registration.
+    ///         Args:
+    ///             name (str): The name used to refer to the condition.
+    ///             func (ConditionFunction): The condition.
+    pub fn register_condition(&mut self, name: &str, func: ConditionFunction<S, E>) {
+        self.conditions
+            .insert(name.to_string(), func.or_named(name));
+    }
+
+    /// Return the callback registered under the passed name.
+    pub fn callback(&self, name: &str) -> Option<&TriggerFunction<S, E>> {
+        self.callbacks.get(name)
+    }
+
+    /// Return the condition registered under the passed name.
+    pub fn condition(&self, name: &str) -> Option<&ConditionFunction<S, E>> {
+        self.conditions.get(name)
+    }
+
+    /// The names of all registered callbacks.
+    pub fn callback_names(&self) -> impl Iterator<Item = &str> {
+        self.callbacks.keys().map(|k| k.as_str())
+    }
+}
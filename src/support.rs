@@ -1,8 +1,13 @@
-//!
+//! Small helpers shared by the other modules.
 
+use crate::error::Error;
+use crate::Result;
+use serde::de::{
+    self, value::SeqAccessDeserializer, Deserialize, Deserializer, SeqAccess, Visitor,
+};
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct KeySet {}
 
 impl Display for KeySet {
@@ -24,20 +29,73 @@ impl Display for KeySet {
 //
 // return obj if isinstance(obj, (list, tuple, EnumMeta)) else [obj]
 
-// def _prep_ordered_arg(desired_length, arguments=None):
-// """Ensure list of arguments passed to add_ordered_transitions has the proper length.
-//     Expands the given arguments and apply same condition, callback
-//     to all transitions if only one has been given.
-//     Args:
-//         desired_length (int): The size of the resulting list
-//         arguments (optional[str, reference or list]): Parameters to be expanded.
-//     Returns:
-//         list: Parameter sets with the desired length.
-//     """
-// arguments = listify(arguments) if arguments is not None else [None]
-// if len(arguments) != desired_length and len(arguments) != 1:
-// raise ValueError("Argument length must be either 1 or the same length as "
-// "the number of transitions.")
-// if len(arguments) == 1:
-// return arguments * desired_length
-// return arguments
+/// Deserialize a single name or a list of names into a list, e.g. the ``source`` of a transition
+///     or its ``conditions``. ``null`` results in an empty list.
+pub(crate) fn listify<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ListifyVisitor;
+
+    impl<'de> Visitor<'de> for ListifyVisitor {
+        type Value = Vec<String>;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> FmtResult {
+            f.write_str("a string or a list of strings")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Self::Value, E> {
+            Ok(vec![value.to_string()])
+        }
+
+        fn visit_unit<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+            Ok(Vec::new())
+        }
+
+        fn visit_none<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+            Ok(Vec::new())
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> std::result::Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(
+            self,
+            seq: A,
+        ) -> std::result::Result<Self::Value, A::Error> {
+            Vec::deserialize(SeqAccessDeserializer::new(seq))
+        }
+    }
+
+    deserializer.deserialize_any(ListifyVisitor)
+}
+
+/// Ensure list of arguments passed to add_ordered_transitions has the proper length.
+///     Expands the given arguments and apply same condition, callback
+///     to all transitions if only one has been given.
+///     Args:
+///         desired_length (int): The size of the resulting list
+///         arguments (list): Parameters to be expanded. An empty list is treated like no parameters.
+///     Returns:
+///         list: Parameter sets with the desired length.
+pub(crate) fn _prep_ordered_arg<T: Clone + Default>(
+    desired_length: usize,
+    arguments: Vec<T>,
+) -> Result<Vec<T>> {
+    let arguments = if arguments.is_empty() {
+        vec![T::default()]
+    } else {
+        arguments
+    };
+    if arguments.len() != desired_length && arguments.len() != 1 {
+        return Err(Error::ArgumentsError);
+    }
+    if arguments.len() == 1 {
+        return Ok(vec![arguments[0].clone(); desired_length]);
+    }
+    Ok(arguments)
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn conditions_see_trigger_arguments() {
+    let m = machine(
+        vec!["A".into(), "B".into()],
+        "A",
+        vec![
+            TransitionParameters::new("withdraw", &["A"], "B").conditions(ConditionFunction::new(
+                |ed: &EventData| {
+                    ed.args()
+                        .kwarg::<u32>("amount")
+                        .is_some_and(|amount| *amount < 100)
+                },
+                None,
+            )),
+        ],
+    );
+    let model = m.model().unwrap().clone();
+    let large = EventArgs::new().with_kwarg("amount", 500u32);
+    assert!(!m.trigger_with_args(&model, "withdraw", large).unwrap());
+    assert!(!m.trigger(&model, "withdraw").unwrap());
+    let small = EventArgs::new().with_kwarg("amount", 5u32);
+    assert!(m.trigger_with_args(&model, "withdraw", small).unwrap());
+}
+
+#[test]
+fn arguments_reach_every_callback() {
+    let log = log();
+    let record = |name: &'static str| {
+        let log = log.clone();
+        TriggerFunction::new(
+            move |ed: &EventData| {
+                let note = ed.args().arg::<String>(0).cloned().unwrap_or_default();
+                log.borrow_mut().push(format!("{} {}", name, note));
+            },
+            None,
+        )
+    };
+    let m = builder(
+        vec![
+            State::new("A".into(), None, Some(vec![record("exit")]), None),
+            State::new("B".into(), Some(vec![record("enter")]), None, None),
+        ],
+        "A",
+        vec![TransitionParameters::new("go", &["A"], "B")
+            .conditions(ConditionFunction::new(
+                |ed: &EventData| ed.args().len() == 1,
+                None,
+            ))
+            .prepare(record("prepare"))
+            .before(record("before"))
+            .after(record("after"))],
+    )
+    .prepare_event(record("prepare_event"))
+    .finalize_event(record("finalize_event"))
+    .build();
+    assert!(m
+        .dispatch_with_args("go", EventArgs::new().with_arg(String::from("x")))
+        .unwrap());
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "prepare_event x",
+            "prepare x",
+            "before x",
+            "exit x",
+            "enter x",
+            "after x",
+            "finalize_event x"
+        ]
+    );
+}
+
+#[test]
+fn arguments_are_looked_up_by_type() {
+    let args = EventArgs::new()
+        .with_arg(7u32)
+        .with_kwarg("note", String::from("rush"));
+    assert_eq!(args.arg::<u32>(0), Some(&7));
+    assert_eq!(args.arg::<i64>(0), None);
+    assert_eq!(args.arg::<u32>(1), None);
+    assert_eq!(
+        args.kwarg::<String>("note").map(String::as_str),
+        Some("rush")
+    );
+    assert_eq!(args.keys().collect::<Vec<_>>(), vec!["note"]);
+    assert_eq!(args.len(), 2);
+    assert!(!args.is_empty());
+    assert!(EventArgs::new().is_empty());
+    let kwargs = EventArgs::new().with_kwarg("x", 1);
+    assert_eq!(kwargs.len(), 1);
+    assert!(!kwargs.is_empty());
+}
+
+fn unwrapped(transitions: Vec<TransitionParameters>) -> Machine {
+    builder(vec!["A".into(), "B".into()], "A", transitions)
+        .send_event(false)
+        .build()
+}
+
+#[test]
+fn callbacks_receive_only_arguments_without_send_event() {
+    let seen = Rc::new(RefCell::new(0u32));
+    let store = seen.clone();
+    let m = unwrapped(vec![TransitionParameters::new("go", &["A"], "B")
+        .conditions(ConditionFunction::with_args(
+            |args: &EventArgs| args.arg::<u32>(0).is_some(),
+            None,
+        ))
+        .after(TriggerFunction::with_args(
+            move |args: &EventArgs| *store.borrow_mut() = *args.arg::<u32>(0).unwrap(),
+            None,
+        ))]);
+    assert!(!m.dispatch("go").unwrap());
+    assert!(m
+        .dispatch_with_args("go", EventArgs::new().with_arg(7u32))
+        .unwrap());
+    assert_eq!(*seen.borrow(), 7);
+}
+
+#[test]
+fn callables_must_match_send_event() {
+    let log = log();
+    let rejected = |result: Result<Machine>| matches!(result, Err(Error::MachineError(_)));
+    let without_event =
+        |transitions| builder(vec!["A".into(), "B".into()], "A", transitions).send_event(false);
+    assert!(rejected(
+        without_event(vec![
+            TransitionParameters::new("go", &["A"], "B").after(push(&log, "after"))
+        ])
+        .try_build()
+    ));
+    assert!(rejected(
+        without_event(vec![TransitionParameters::new("go", &["A"], "B")
+            .conditions(Condition::any(vec![ConditionFunction::new(
+                |_| true,
+                None
+            )]))])
+        .try_build()
+    ));
+    assert!(rejected(
+        without_event(Vec::new())
+            .prepare_event(push(&log, "prepare"))
+            .try_build()
+    ));
+    assert!(rejected(
+        builder(
+            vec![State::new(
+                "A".into(),
+                Some(vec![push(&log, "enter")]),
+                None,
+                None
+            )],
+            "A",
+            Vec::new(),
+        )
+        .send_event(false)
+        .try_build()
+    ));
+
+    let mut m = machine(vec!["A".into(), "B".into()], "A", Vec::new());
+    let before = TriggerFunction::with_args(|_: &EventArgs| {}, None);
+    assert!(matches!(
+        m.add_transition(
+            "go",
+            &["A"],
+            Some("B"),
+            Default::default(),
+            Default::default(),
+            before.into(),
+            Default::default(),
+            Default::default()
+        ),
+        Err(Error::MachineError(_))
+    ));
+    assert!(m.get_event("go").is_err());
+    assert!(matches!(
+        m.register_condition("ok", ConditionFunction::with_args(|_| true, None)),
+        Err(Error::MachineError(_))
+    ));
+    assert!(m.registry().condition("ok").is_none());
+    assert!(log.borrow().is_empty());
+    assert!(push(&log, "x").sends_event());
+    assert!(!TriggerFunction::<String>::with_args(|_: &EventArgs| {}, None).sends_event());
+}
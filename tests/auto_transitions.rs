@@ -0,0 +1,55 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+fn auto(auto_transitions: bool) -> Machine {
+    builder(
+        vec!["A".into(), "B".into(), "C".into()],
+        "A",
+        vec![TransitionParameters::new("advance", &["A"], "B")],
+    )
+    .auto_transitions(auto_transitions)
+    .build()
+}
+
+#[test]
+fn every_state_gets_an_auto_transition() {
+    let mut m = auto(true);
+    let model = m.model().unwrap().clone();
+    assert_eq!(
+        m.get_triggers(&["A"]),
+        vec!["to_A", "to_B", "to_C", "advance"]
+    );
+    assert!(m.trigger(&model, "to_C").unwrap());
+    assert_eq!(state_of(&m, &model), "C");
+    assert!(m.trigger(&model, "to_C").unwrap());
+    m.add_states(vec!["D".into()], vec![], vec![], None)
+        .unwrap();
+    assert!(m.trigger(&model, "to_D").unwrap());
+    assert!(m.trigger(&model, "to_A").unwrap());
+    assert_eq!(state_of(&m, &model), "A");
+}
+
+#[test]
+fn auto_transitions_can_be_removed() {
+    let mut m = auto(true);
+    m.remove_transition("to_B", Some("C"), None).unwrap();
+    assert_eq!(m.get_triggers(&["C"]), vec!["to_A", "to_C"]);
+    assert_eq!(
+        m.get_triggers(&["A"]),
+        vec!["to_A", "to_B", "to_C", "advance"]
+    );
+    m.remove_transition::<_, &str>("to_B", None, None).unwrap();
+    assert!(matches!(
+        m.get_event("to_B"),
+        Err(Error::UnknownEventError(name)) if name == "to_B"
+    ));
+}
+
+#[test]
+fn auto_transitions_can_be_disabled() {
+    let m = auto(false);
+    assert_eq!(m.get_triggers(&["A"]), vec!["advance"]);
+    assert!(m.dispatch("to_C").is_err());
+}
@@ -0,0 +1,184 @@
+#![allow(dead_code)]
+
+use rustitions::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A domain model driven by a machine. It keeps one state per model attribute.
+#[derive(Debug, Default)]
+pub struct Order {
+    pub states: HashMap<String, String>,
+}
+
+impl Order {
+    pub fn shared() -> Rc<RefCell<Order>> {
+        Rc::new(RefCell::new(Order::default()))
+    }
+
+    pub fn state(&self) -> Option<&str> {
+        self.states.get("state").map(String::as_str)
+    }
+}
+
+impl Model for Order {
+    fn get_state(&self, model_attribute: &str) -> Option<String> {
+        self.states.get(model_attribute).cloned()
+    }
+
+    fn set_state(&mut self, model_attribute: &str, state: String) {
+        self.states.insert(model_attribute.to_string(), state);
+    }
+}
+
+/// Messages recorded by callbacks in the order they have been called.
+pub type Log = Rc<RefCell<Vec<String>>>;
+
+pub fn log() -> Log {
+    Rc::new(RefCell::new(Vec::new()))
+}
+
+/// A callback appending ``message`` to ``log``.
+pub fn push(log: &Log, message: &str) -> TriggerFunction {
+    let log = log.clone();
+    let message = message.to_string();
+    TriggerFunction::new(move |_| log.borrow_mut().push(message.clone()), None)
+}
+
+/// The arguments of ``Machine::new``, defaulting to a machine acting as its own model with
+/// ``send_event`` enabled and without auto transitions. Tests only set the options they change.
+pub struct Builder<S: StateValue = String, E: EventValue = String> {
+    model: Option<ModelRef>,
+    states: Vec<State<S, E>>,
+    initial: Option<State<S, E>>,
+    transitions: Vec<TransitionParameters<S, E>>,
+    send_event: bool,
+    auto_transitions: bool,
+    ignore_invalid_triggers: bool,
+    queued: bool,
+    prepare_event: Option<TriggerFunction<S, E>>,
+    finalize_event: Option<TriggerFunction<S, E>>,
+    model_attribute: Option<String>,
+    on_exception: Option<TriggerFunction<S, E>>,
+}
+
+/// A ``Builder`` for a machine with string states.
+pub fn builder(
+    states: Vec<State>,
+    initial: &str,
+    transitions: Vec<TransitionParameters>,
+) -> Builder {
+    Builder::new(states, initial.into(), transitions)
+}
+
+impl<S: StateValue, E: EventValue> Builder<S, E> {
+    pub fn new(
+        states: Vec<State<S, E>>,
+        initial: State<S, E>,
+        transitions: Vec<TransitionParameters<S, E>>,
+    ) -> Self {
+        Builder {
+            model: None,
+            states,
+            initial: Some(initial),
+            transitions,
+            send_event: true,
+            auto_transitions: false,
+            ignore_invalid_triggers: false,
+            queued: false,
+            prepare_event: None,
+            finalize_event: None,
+            model_attribute: None,
+            on_exception: None,
+        }
+    }
+
+    pub fn model(mut self, model: ModelRef) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    pub fn without_initial(mut self) -> Self {
+        self.initial = None;
+        self
+    }
+
+    pub fn send_event(mut self, send_event: bool) -> Self {
+        self.send_event = send_event;
+        self
+    }
+
+    pub fn auto_transitions(mut self, auto_transitions: bool) -> Self {
+        self.auto_transitions = auto_transitions;
+        self
+    }
+
+    pub fn ignore_invalid_triggers(mut self, ignore_invalid_triggers: bool) -> Self {
+        self.ignore_invalid_triggers = ignore_invalid_triggers;
+        self
+    }
+
+    pub fn queued(mut self, queued: bool) -> Self {
+        self.queued = queued;
+        self
+    }
+
+    pub fn prepare_event(mut self, prepare_event: TriggerFunction<S, E>) -> Self {
+        self.prepare_event = Some(prepare_event);
+        self
+    }
+
+    pub fn finalize_event(mut self, finalize_event: TriggerFunction<S, E>) -> Self {
+        self.finalize_event = Some(finalize_event);
+        self
+    }
+
+    pub fn model_attribute(mut self, model_attribute: &str) -> Self {
+        self.model_attribute = Some(model_attribute.to_string());
+        self
+    }
+
+    pub fn on_exception(mut self, on_exception: TriggerFunction<S, E>) -> Self {
+        self.on_exception = Some(on_exception);
+        self
+    }
+
+    pub fn try_build(self) -> Result<Machine<S, E>> {
+        Machine::new(
+            self.model,
+            self.states,
+            self.initial,
+            self.transitions,
+            self.send_event,
+            self.auto_transitions,
+            false,
+            self.ignore_invalid_triggers,
+            None,
+            None,
+            None,
+            self.queued,
+            self.prepare_event,
+            self.finalize_event,
+            self.model_attribute,
+            self.on_exception,
+        )
+    }
+
+    pub fn build(self) -> Machine<S, E> {
+        self.try_build().unwrap()
+    }
+}
+
+/// A machine acting as its own model with ``send_event`` enabled and without auto transitions.
+pub fn machine(
+    states: Vec<State>,
+    initial: &str,
+    transitions: Vec<TransitionParameters>,
+) -> Machine {
+    builder(states, initial, transitions).build()
+}
+
+/// The name of the state ``model`` is currently in.
+pub fn state_of(machine: &Machine, model: &ModelRef) -> String {
+    machine.get_model_state(model).unwrap().name().to_string()
+}
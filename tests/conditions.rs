@@ -0,0 +1,78 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+fn constant(value: bool, name: &str) -> ConditionFunction {
+    ConditionFunction::new(move |_| value, Some(name.into()))
+}
+
+fn guarded(transitions: Vec<TransitionParameters>) -> Machine {
+    machine(vec!["A".into(), "B".into()], "A", transitions)
+}
+
+#[test]
+fn unless_blocks_when_true() {
+    let m = guarded(vec![
+        TransitionParameters::new("go", &["A"], "B").unless(constant(true, "blocked"))
+    ]);
+    assert!(!m.dispatch("go").unwrap());
+    let m = guarded(vec![TransitionParameters::new("go", &["A"], "B")
+        .conditions(constant(true, "ready"))
+        .unless(constant(false, "blocked"))]);
+    assert!(m.dispatch("go").unwrap());
+}
+
+#[test]
+fn conditions_and_unless_must_all_pass() {
+    let m = guarded(vec![TransitionParameters::new("go", &["A"], "B")
+        .conditions(vec![constant(true, "ready"), constant(true, "paid")])
+        .unless(vec![constant(false, "blocked"), constant(true, "locked")])]);
+    assert!(!m.dispatch("go").unwrap());
+    assert_eq!(state_of(&m, m.model().unwrap()), "A");
+}
+
+#[test]
+fn conditions_are_named_after_their_function() {
+    assert_eq!(constant(true, "ready").name(), Some("ready"));
+    assert_eq!(Condition::from(constant(true, "ready")).name(), "ready");
+    assert_eq!(
+        ConditionFunction::<String>::new(|_| true, None).name(),
+        None
+    );
+}
+
+#[test]
+fn combined_conditions() {
+    let t = || constant(true, "t");
+    let f = || constant(false, "f");
+    let m = guarded(vec![
+        TransitionParameters::new("no", &["A"], "B").conditions(Condition::any(vec![
+            Condition::all(vec![t(), f()]),
+            Condition::not(t()),
+        ])),
+        TransitionParameters::new("yes", &["A"], "B").conditions(vec![
+            Condition::any(vec![f(), t()]),
+            Condition::not(Condition::all(vec![t(), f()])),
+        ]),
+    ]);
+    assert!(!m.dispatch("no").unwrap());
+    assert!(m.dispatch("yes").unwrap());
+}
+
+#[test]
+fn combined_conditions_in_unless() {
+    let m = guarded(vec![TransitionParameters::new("go", &["A"], "B").unless(
+        Condition::any(vec![constant(false, "locked"), constant(false, "closed")]),
+    )]);
+    assert!(m.dispatch("go").unwrap());
+}
+
+#[test]
+fn combined_conditions_are_named_after_their_parts() {
+    let condition = Condition::any(vec![
+        Condition::all(vec![constant(true, "t"), constant(false, "f")]),
+        Condition::not(constant(true, "t")),
+    ]);
+    assert_eq!(condition.name(), "any(all(t, f), not(t))");
+}
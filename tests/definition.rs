@@ -0,0 +1,337 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+fn registry(log: &Log) -> CallbackRegistry {
+    let mut registry = CallbackRegistry::new();
+    let enter = log.clone();
+    registry.register_callback(
+        "log_enter",
+        TriggerFunction::new(
+            move |ed: &EventData| {
+                enter
+                    .borrow_mut()
+                    .push(format!("enter {}", ed.state().name()))
+            },
+            None,
+        ),
+    );
+    registry.register_callback("on_enter_C", push(log, "dynamic C"));
+    registry.register_callback("ping", push(log, "ping"));
+    registry.register_condition("yes", ConditionFunction::new(|_: &EventData| true, None));
+    registry.register_condition("no", ConditionFunction::new(|_: &EventData| false, None));
+    registry
+}
+
+const JSON: &str = r#"{
+    "name": "json",
+    "states": ["A", {"name": "B", "on_enter": "log_enter"}, {"name": "C", "ignore_invalid_triggers": true}],
+    "initial": "A",
+    "send_event": true,
+    "auto_transitions": false,
+    "transitions": [
+        ["advance", "A", "B"],
+        {"trigger": "advance", "source": "B", "dest": "C", "conditions": ["yes"], "unless": "no"},
+        {"trigger": "poke", "source": "*", "dest": null, "after": "ping"},
+        ["reset", ["B", "C"], "A", null, "yes"]
+    ],
+    "finalize_event": "ping",
+    "models": []
+}"#;
+
+#[test]
+fn json_definitions_build_machines() {
+    let log = log();
+    let m: Machine = Machine::from_json(JSON, registry(&log)).unwrap();
+    let model = m.models()[0].clone();
+    assert!(m.trigger(&model, "advance").unwrap());
+    assert!(m.trigger(&model, "advance").unwrap());
+    assert!(m.is_state("C", &model));
+    assert!(m.trigger(&model, "poke").unwrap());
+    assert!(m.is_state("C", &model));
+    assert!(!m.trigger(&model, "reset").unwrap());
+    assert!(!m.trigger(&model, "to_A").unwrap());
+    assert!(!m.trigger(&model, "advance").unwrap());
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "enter B",
+            "ping",
+            "dynamic C",
+            "ping",
+            "ping",
+            "ping",
+            "ping",
+            "ping"
+        ]
+    );
+}
+
+#[test]
+fn json_definitions_are_validated() {
+    let build = |json: &str| Machine::<String>::from_json(json, CallbackRegistry::new());
+    match build("{\n  \"states\": [1]\n}") {
+        Err(Error::DefinitionError(msg, 2, _)) => assert!(msg.contains("state name"), "{}", msg),
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    assert!(matches!(
+        build(r#"{"states": ["A"], "transitions": [["go", "A", "B"]]}"#),
+        Err(Error::UnknownStateError(entry)) if entry == "transitions[0].dest: B"
+    ));
+    assert!(matches!(
+        build(r#"{"states": ["A"], "initial": "B"}"#).map(|m| m.initial().map(String::from)),
+        Ok(Some(initial)) if initial == "B"
+    ));
+    assert!(matches!(
+        build(r#"{"states": ["A"], "transitions": [{"trigger": "go", "source": "A", "dest": "A", "before": "nope"}]}"#),
+        Err(Error::CallableError(entry)) if entry == "transitions[0].before: nope"
+    ));
+    for json in &[
+        r#"{"states": ["A", "B"], "transitions": [{"trigger": "go", "source": "A"}]}"#,
+        r#"{"states": ["A", "B"], "transitions": [{"trigger": "go", "source": "A", "destination": "B"}]}"#,
+        r#"{"states": [{"name": "A", "on_entry": "x"}]}"#,
+    ] {
+        assert!(
+            matches!(build(json), Err(Error::DefinitionError(..))),
+            "{}",
+            json
+        );
+    }
+}
+
+#[test]
+fn json_definitions_match_send_event() {
+    let log = log();
+    let build = |json: &str| Machine::<String>::from_json(json, registry(&log));
+    assert!(matches!(
+        build(r#"{"states": ["A", "B"], "transitions": [{"trigger": "go", "source": "A", "dest": "B", "after": "ping"}]}"#),
+        Err(Error::MachineError(entry)) if entry == "transitions[0].after: ping"
+    ));
+    assert!(build(
+        r#"{"states": ["A", "B"], "send_event": true, "transitions": [["go", "A", "B", "yes"]]}"#
+    )
+    .is_ok());
+    assert!(matches!(
+        build(r#"{"states": ["A", "B"], "transitions": [["go", "A", "B", "yes"]]}"#),
+        Err(Error::MachineError(entry)) if entry == "transitions[0].conditions: yes"
+    ));
+    // like callbacks registered with the machine, unreferenced callbacks have to match as well
+    assert!(matches!(
+        build(r#"{"states": ["A", "C"]}"#),
+        Err(Error::MachineError(_))
+    ));
+    assert!(log.borrow().is_empty());
+}
+
+#[test]
+fn json_definitions_accept_null_settings() {
+    let build = |json: &str| Machine::<String>::from_json(json, CallbackRegistry::new());
+    let m = build(r#"{"states": ["A"], "initial": "A", "ignore_invalid_triggers": null}"#).unwrap();
+    assert!(m.dispatch("nope").is_err());
+    assert_eq!(
+        m.to_definition().unwrap().ignore_invalid_triggers,
+        Some(false)
+    );
+    let m = build(r#"{"states": ["A"], "initial": "A", "ignore_invalid_triggers": true}"#).unwrap();
+    assert!(!m.dispatch("nope").unwrap());
+    let m = build(r#"{"states": null, "transitions": null, "initial": "A"}"#).unwrap();
+    assert_eq!(m.states().count(), 1);
+    assert!(m.to_definition().unwrap().transitions.is_empty());
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, strum_macros::AsRefStr, strum_macros::EnumString)]
+enum Phase {
+    Solid,
+    Liquid,
+}
+
+#[test]
+fn json_definitions_parse_typed_states() {
+    let build = |json: &str| Machine::<Phase>::from_json(json, CallbackRegistry::new());
+    let m = build(r#"{"states": ["Solid", "Liquid"], "initial": "Solid", "transitions": [["melt", "Solid", "Liquid"]]}"#)
+        .unwrap();
+    assert!(m.dispatch("melt").unwrap());
+    assert!(m.is_state(Phase::Liquid, &m.models()[0]));
+    assert!(matches!(
+        build(r#"{"states": ["Solid", "Plasma"], "initial": "Solid"}"#),
+        Err(Error::UnknownStateError(entry)) if entry == "states[1].name: Plasma"
+    ));
+}
+
+const YAML: &str = "
+name: flow
+initial: draft
+send_event: true
+states:
+  - draft
+  - name: review
+    on_enter: ping
+  - done
+transitions:
+  - [submit, draft, review]
+  - trigger: approve
+    source: review
+    dest: done
+    conditions: yes
+  - trigger: comment
+    source: '*'
+    dest: ~
+";
+
+#[test]
+fn yaml_definitions_build_machines() {
+    let log = log();
+    let m: Machine = Machine::from_yaml(YAML, registry(&log)).unwrap();
+    assert!(m.dispatch("submit").unwrap());
+    assert!(m.dispatch("comment").unwrap());
+    assert!(m.is_state("review", &m.models()[0]));
+    assert!(m.dispatch("approve").unwrap());
+    assert!(m.is_state("done", &m.models()[0]));
+    assert_eq!(*log.borrow(), vec!["ping"]);
+}
+
+#[test]
+fn yaml_errors_are_located() {
+    let build = |yaml: &str| Machine::<String>::from_yaml(yaml, registry(&log()));
+    match build("states:\n  - a\n - b\n") {
+        Err(Error::DefinitionError(msg, line, column)) => {
+            assert_eq!((line, column), (3, 2), "{}", msg);
+            assert!(!msg.contains("at line"), "{}", msg);
+        }
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    match build("states: [a]\ntransitions:\n  - {trigger: t, source: a, dest: null}\n  - [x]\n") {
+        Err(Error::DefinitionError(_, line, _)) => assert_eq!(line, 4),
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    for (yaml, location) in &[("", (1, 1)), ("# nothing\n", (2, 1))] {
+        match build(yaml) {
+            Err(Error::DefinitionError(_, line, column)) => assert_eq!((line, column), *location),
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn yaml_errors_name_the_entry() {
+    let build = |yaml: &str| Machine::<String>::from_yaml(yaml, registry(&log()));
+    assert!(matches!(
+        build(&YAML.replace("conditions: yes", "conditions: missing")),
+        Err(Error::CallableError(entry)) if entry == "transitions[1].conditions: missing"
+    ));
+    assert!(matches!(
+        build(&YAML.replace("on_enter: ping", "on_enter: [ping, gone]")),
+        Err(Error::CallableError(entry)) if entry == "states[1].on_enter: gone"
+    ));
+    assert!(matches!(
+        build(&YAML.replace("[submit, draft, review]", "[submit, drafts, review]")),
+        Err(Error::UnknownStateError(entry)) if entry == "transitions[0].source: drafts"
+    ));
+}
+
+const EXPORTED: &str = r#"{
+    "name": "export",
+    "states": ["A", {"name": "B", "on_exit": ["ping"], "ignore_invalid_triggers": true}, "C"],
+    "initial": "A",
+    "send_event": true,
+    "ordered_transitions": true,
+    "transitions": [
+        ["advance", "A", "B", "yes"],
+        {"trigger": "poke", "source": "*", "dest": null, "unless": "no", "after": "ping"},
+        {"trigger": "stay", "source": ["B", "C"], "dest": "=", "prepare": ["ping"]},
+        {"trigger": "to_C", "source": "B", "dest": "C", "conditions": "yes"}
+    ],
+    "finalize_event": "ping"
+}"#;
+
+#[test]
+fn machines_export_their_definition() {
+    let log = log();
+    let registry = registry(&log);
+    let m: Machine = Machine::from_json(EXPORTED, registry.clone()).unwrap();
+    let definition = m.to_definition().unwrap();
+    assert_eq!(definition.name.as_deref(), Some("export"));
+    assert!(!definition.ordered_transitions);
+    assert_eq!(definition.finalize_event, vec!["ping"]);
+    assert_eq!(definition.states[1].on_exit, vec!["ping"]);
+    assert_eq!(definition.states[1].ignore_invalid_triggers, Some(true));
+    let advance = definition
+        .transitions
+        .iter()
+        .find(|t| t.trigger == "advance")
+        .unwrap();
+    assert_eq!(advance.source, vec!["A"]);
+    assert_eq!(advance.conditions, vec!["yes"]);
+    let poke: Vec<_> = definition
+        .transitions
+        .iter()
+        .filter(|t| t.trigger == "poke")
+        .collect();
+    assert_eq!(poke.len(), 3);
+    assert!(poke.iter().all(|t| t.dest.is_none() && t.unless == ["no"]));
+    let stay = definition
+        .transitions
+        .iter()
+        .find(|t| t.trigger == "stay" && t.source == ["C"])
+        .unwrap();
+    assert_eq!(stay.dest.as_deref(), Some("C"));
+    assert!(definition
+        .transitions
+        .iter()
+        .any(|t| t.trigger == "next_state"));
+}
+
+#[test]
+fn exports_skip_only_auto_transitions() {
+    let m: Machine = Machine::from_json(EXPORTED, registry(&log())).unwrap();
+    let definition = m.to_definition().unwrap();
+    let auto: Vec<_> = definition
+        .transitions
+        .iter()
+        .filter(|t| t.trigger.starts_with("to_"))
+        .collect();
+    assert_eq!(auto.len(), 1);
+    assert_eq!(auto[0].trigger, "to_C");
+    assert_eq!(auto[0].source, vec!["B"]);
+    assert_eq!(auto[0].conditions, vec!["yes"]);
+}
+
+#[test]
+fn exported_definitions_load_again() {
+    let registry = registry(&log());
+    let m: Machine = Machine::from_json(EXPORTED, registry.clone()).unwrap();
+    let definition = m.to_definition().unwrap();
+    let json = m.to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value.get("ordered_transitions").is_none());
+    assert!(value["states"][0].get("on_enter").is_none());
+    let from_json: Machine = Machine::from_json(&json, registry.clone()).unwrap();
+    assert_eq!(from_json.to_definition().unwrap(), definition);
+    let yaml = serde_yaml::to_string(&definition).unwrap();
+    let from_yaml: Machine = Machine::from_yaml(&yaml, registry).unwrap();
+    assert_eq!(from_yaml.to_definition().unwrap(), definition);
+    assert!(from_yaml.dispatch("advance").unwrap());
+    assert!(from_yaml.dispatch("to_C").unwrap());
+    assert!(from_yaml.dispatch("next_state").unwrap());
+}
+
+#[test]
+fn unrepresentable_callables_fail_the_export() {
+    let mut m: Machine = Machine::from_json(EXPORTED, registry(&log())).unwrap();
+    m.prepare_event
+        .push(TriggerFunction::new(|_: &EventData| {}, None));
+    assert!(matches!(
+        m.to_definition(),
+        Err(Error::ExportError(entry)) if entry.starts_with("prepare_event ")
+    ));
+    assert!(matches!(m.to_json(), Err(Error::ExportError(_))));
+    m.prepare_event.clear();
+    m.add_transitions(vec![TransitionParameters::new("poke", &["A"], "B")
+        .conditions(Condition::any(vec![ConditionFunction::named("yes")]))])
+        .unwrap();
+    assert!(matches!(
+        m.to_definition(),
+        Err(Error::ExportError(entry)) if entry.starts_with("transitions[poke from A].conditions ")
+    ));
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+fn transition(source: &str, dest: &str) -> Transition {
+    Transition::new(
+        source.to_string(),
+        Some(dest.to_string()),
+        PotentialConditions::default(),
+        PotentialConditions::default(),
+        PotentialTriggers::default(),
+        PotentialTriggers::default(),
+        PotentialTriggers::default(),
+    )
+}
+
+#[test]
+fn transitions_are_grouped_by_source() {
+    let mut event: Event = Event::new("advance".into());
+    assert!(event.is_empty());
+    event.add_transition(transition("A", "B"));
+    event.add_transition(transition("B", "C"));
+    event.add_transition(transition("A", "C"));
+    let dests: Vec<_> = event.transitions("A").iter().map(|t| t.dest()).collect();
+    assert_eq!(dests, vec![Some("B"), Some("C")]);
+    assert_eq!(event.transitions("B").len(), 1);
+    assert!(event.transitions("C").is_empty());
+    assert!(!event.is_empty());
+}
+
+#[test]
+fn add_callback_reaches_every_transition() {
+    let log = log();
+    let m = machine(vec!["A".into(), "B".into(), "C".into()], "A", vec![]);
+    let model = m.model().unwrap().clone();
+    let mut event: Event = Event::new("advance".into());
+    for (source, dest) in &[("A", "B"), ("B", "C"), ("C", "A")] {
+        event.add_transition(transition(source, dest));
+    }
+    event.add_callback(TransitionTriggerType::Prepare, push(&log, "prepare"));
+    event.add_callback(TransitionTriggerType::Before, push(&log, "before"));
+    event.add_callback(TransitionTriggerType::After, push(&log, "after"));
+    for source in &["A", "B", "C"] {
+        let state = m.get_state(*source).unwrap();
+        let mut event_data = EventData::new(state, &event, &m, &model, EventArgs::new());
+        assert!(event.transitions(source)[0]
+            .execute(&mut event_data)
+            .unwrap());
+    }
+    assert_eq!(state_of(&m, &model), "A");
+    assert_eq!(log.borrow().len(), 9);
+    assert_eq!(log.borrow()[..3], ["prepare", "before", "after"]);
+}
+
+#[test]
+fn add_transition_rejects_the_model_attribute() {
+    let mut m = machine(vec!["A".into(), "B".into()], "A", vec![]);
+    assert!(matches!(
+        m.add_transitions(vec![TransitionParameters::new("state", &["A"], "B")]),
+        Err(Error::TriggerNameError(name)) if name == "state"
+    ));
+}
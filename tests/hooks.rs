@@ -0,0 +1,157 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+fn hooked(states: Vec<State>, transitions: Vec<TransitionParameters>, log: &Log) -> Builder {
+    let prepare = {
+        let log = log.clone();
+        TriggerFunction::new(
+            move |ed: &EventData| {
+                log.borrow_mut()
+                    .push(format!("prepare {}", ed.state().name()))
+            },
+            None,
+        )
+    };
+    let finalize = {
+        let log = log.clone();
+        TriggerFunction::new(
+            move |ed: &EventData| {
+                let error = ed.error().map(|e| e.to_string()).unwrap_or_default();
+                log.borrow_mut().push(
+                    format!("finalize {} {} {}", ed.state().name(), ed.result(), error)
+                        .trim_end()
+                        .to_string(),
+                )
+            },
+            None,
+        )
+    };
+    builder(states, "A", transitions)
+        .prepare_event(prepare)
+        .finalize_event(finalize)
+}
+
+#[test]
+fn prepare_and_finalize_wrap_transitions() {
+    let log = log();
+    let m = hooked(
+        vec!["A".into(), "B".into()],
+        vec![
+            TransitionParameters::new("advance", &["A"], "B")
+                .prepare(push(&log, "prepare transition"))
+                .after(push(&log, "after")),
+            TransitionParameters::new("blocked", &["B"], "A")
+                .conditions(ConditionFunction::new(|_| false, None)),
+        ],
+        &log,
+    )
+    .build();
+    assert!(m.dispatch("advance").unwrap());
+    assert!(!m.dispatch("blocked").unwrap());
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "prepare A",
+            "prepare transition",
+            "after",
+            "finalize B true",
+            "prepare B",
+            "finalize B false"
+        ]
+    );
+}
+
+fn failing(log: &Log) -> Builder {
+    hooked(
+        vec![
+            "A".into(),
+            State::new(
+                "B".into(),
+                Some(vec![TriggerFunction::fallible(
+                    |_| Err(Error::MachineError("boom".into())),
+                    None,
+                )]),
+                None,
+                None,
+            ),
+        ],
+        vec![TransitionParameters::new("advance", &["A"], "B").after(push(log, "after"))],
+        log,
+    )
+}
+
+#[test]
+fn finalize_runs_for_invalid_and_ignored_triggers() {
+    let log = log();
+    let m = hooked(
+        vec!["A".into(), "B".into()],
+        vec![TransitionParameters::new("back", &["B"], "A")],
+        &log,
+    )
+    .build();
+    assert!(m.dispatch("back").is_err());
+    assert_eq!(
+        *log.borrow(),
+        vec!["finalize A false MachineError: Can't trigger event back from state A!"]
+    );
+    let log = common::log();
+    let m = hooked(
+        vec!["A".into(), "B".into()],
+        vec![TransitionParameters::new("back", &["B"], "A")],
+        &log,
+    )
+    .ignore_invalid_triggers(true)
+    .build();
+    assert!(!m.dispatch("back").unwrap());
+    assert_eq!(*log.borrow(), vec!["finalize A false"]);
+}
+
+#[test]
+fn errors_are_raised_and_recorded() {
+    let log = log();
+    let m = failing(&log).build();
+    assert!(matches!(
+        m.dispatch("advance"),
+        Err(Error::MachineError(msg)) if msg == "boom"
+    ));
+    assert_eq!(
+        *log.borrow(),
+        vec!["prepare A", "finalize B false MachineError: boom"]
+    );
+}
+
+#[test]
+fn on_exception_handles_errors() {
+    let log = log();
+    let m = failing(&log).on_exception(push(&log, "handled")).build();
+    assert!(!m.dispatch("advance").unwrap());
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "prepare A",
+            "handled",
+            "finalize B false MachineError: boom"
+        ]
+    );
+}
+
+#[test]
+fn on_exception_can_reraise() {
+    let log = log();
+    let m = failing(&log)
+        .on_exception(TriggerFunction::fallible(
+            |ed: &EventData| Err(ed.error().unwrap().clone()),
+            None,
+        ))
+        .build();
+    assert!(matches!(
+        m.dispatch("advance"),
+        Err(Error::MachineError(msg)) if msg == "boom"
+    ));
+    assert_eq!(
+        *log.borrow(),
+        vec!["prepare A", "finalize B false MachineError: boom"]
+    );
+}
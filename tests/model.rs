@@ -0,0 +1,122 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+use std::rc::Rc;
+
+fn advance() -> Builder {
+    builder(
+        vec!["A".into(), "B".into()],
+        "A",
+        vec![TransitionParameters::new("advance", &["A"], "B")],
+    )
+    .auto_transitions(true)
+}
+
+#[test]
+fn custom_model_is_driven_by_machine() {
+    let order = Order::shared();
+    let m = advance().model(order.clone()).build();
+    assert_eq!(order.borrow().state(), Some("A"));
+    let model: ModelRef = order.clone();
+    assert!(m.trigger(&model, "advance").unwrap());
+    assert_eq!(order.borrow().state(), Some("B"));
+}
+
+#[test]
+fn state_is_stored_under_model_attribute() {
+    let order = Order::shared();
+    let m = advance()
+        .model(order.clone())
+        .model_attribute("status")
+        .build();
+    assert_eq!(order.borrow().state(), None);
+    assert_eq!(order.borrow().states["status"], "A");
+    assert!(m.dispatch("advance").unwrap());
+    assert_eq!(order.borrow().states["status"], "B");
+    assert!(m.dispatch("to_status_A").unwrap());
+    assert_eq!(order.borrow().states["status"], "A");
+}
+
+#[test]
+fn machine_acts_as_own_model() {
+    let m = advance().build();
+    let model = m.model().unwrap().clone();
+    assert_eq!(state_of(&m, &model), "A");
+    assert!(m.dispatch("advance").unwrap());
+    assert_eq!(state_of(&m, &model), "B");
+}
+
+#[test]
+fn models_are_added_and_removed() {
+    let first = Order::shared();
+    let second = Order::shared();
+    let mut m = advance().model(first.clone()).build();
+    let handle: ModelRef = second.clone();
+    m.add_model(handle.clone(), Some("B".into())).unwrap();
+    m.add_model(handle.clone(), None).unwrap();
+    assert_eq!(m.models().len(), 2);
+    assert_eq!(second.borrow().state(), Some("B"));
+    assert!(matches!(
+        m.add_model(Order::shared(), Some("Z".into())),
+        Err(Error::RegisteredStateError(name)) if name == "Z"
+    ));
+    m.remove_model(&handle);
+    assert_eq!(m.models().len(), 1);
+    m.set_state("B", None).unwrap();
+    assert_eq!(first.borrow().state(), Some("B"));
+    m.set_state("A", None).unwrap();
+    assert_eq!(second.borrow().state(), Some("B"));
+}
+
+#[test]
+fn models_require_an_initial_state() {
+    let m = advance()
+        .model(Order::shared())
+        .without_initial()
+        .try_build();
+    assert!(matches!(m, Err(Error::InitialStateError)));
+}
+
+#[test]
+fn triggers_only_change_the_passed_model() {
+    let first = Order::shared();
+    let second = Order::shared();
+    let mut m = advance().model(first.clone()).build();
+    m.add_model(second.clone(), None).unwrap();
+    let handle: ModelRef = first.clone();
+    assert!(m.trigger(&handle, "advance").unwrap());
+    assert_eq!(first.borrow().state(), Some("B"));
+    assert_eq!(second.borrow().state(), Some("A"));
+    assert!(m.dispatch("to_A").unwrap());
+    assert_eq!(m.models_in_state("A").len(), 2);
+}
+
+#[test]
+fn models_can_be_queried_by_state() {
+    let mut m = machine(vec!["A".into(), "B".into()], "A", vec![]);
+    let first = m.model().unwrap().clone();
+    let second: ModelRef = Order::shared();
+    m.add_model(second.clone(), Some("B".into())).unwrap();
+    assert!(m.is_state("A", &first));
+    assert!(!m.is_state("B", &first));
+    assert_eq!(m.get_model_state(&second).unwrap().name(), "B");
+    let in_b = m.models_in_state("B");
+    assert_eq!(in_b.len(), 1);
+    assert!(Rc::ptr_eq(in_b[0], &second));
+    m.set_state("B", None).unwrap();
+    assert_eq!(m.models_in_state("B").len(), 2);
+    assert!(m.models_in_state("A").is_empty());
+    assert!(m.models_in_state("X").is_empty());
+}
+
+#[test]
+fn model_without_state_is_reported() {
+    let m = machine(vec!["A".into()], "A", vec![]);
+    let detached: ModelRef = Order::shared();
+    assert!(!m.is_state("A", &detached));
+    assert!(matches!(
+        m.get_model_state(&detached),
+        Err(Error::MachineAttributeError(attribute, _)) if attribute == "state"
+    ));
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+fn abc(initial: &str) -> Machine {
+    machine(vec!["A".into(), "B".into(), "C".into()], initial, vec![])
+}
+
+fn walk(m: &Machine, trigger: &str, steps: usize) -> Vec<String> {
+    let model = m.model().unwrap();
+    (0..steps)
+        .map(|_| {
+            m.trigger(model, trigger).unwrap();
+            state_of(m, model)
+        })
+        .collect()
+}
+
+#[test]
+fn ordered_transitions_start_at_initial_and_loop() {
+    let mut m = abc("B");
+    m.add_ordered_transitions::<&str>(
+        None,
+        None,
+        true,
+        true,
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(walk(&m, "next_state", 4), vec!["C", "A", "B", "C"]);
+}
+
+#[test]
+fn loop_can_skip_initial() {
+    let mut m = abc("B");
+    m.add_ordered_transitions::<&str>(
+        None,
+        None,
+        true,
+        false,
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(walk(&m, "next_state", 4), vec!["C", "A", "C", "A"]);
+}
+
+#[test]
+fn ordered_transitions_without_loop_stop_at_last_state() {
+    let mut m = abc("A");
+    m.add_ordered_transitions(
+        Some(&["A", "C"]),
+        Some("go".into()),
+        false,
+        true,
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    )
+    .unwrap();
+    let model = m.model().unwrap().clone();
+    assert!(m.trigger(&model, "go").unwrap());
+    assert_eq!(state_of(&m, &model), "C");
+    assert!(m.trigger(&model, "go").is_err());
+}
+
+#[test]
+fn arguments_are_expanded_per_transition() {
+    let log = log();
+    let mut m = abc("A");
+    m.add_ordered_transitions::<&str>(
+        None,
+        None,
+        true,
+        true,
+        vec![],
+        vec![],
+        vec![push(&log, "before").into()],
+        vec![
+            push(&log, "after A").into(),
+            push(&log, "after B").into(),
+            push(&log, "after C").into(),
+        ],
+        vec![],
+    )
+    .unwrap();
+    walk(&m, "next_state", 3);
+    assert_eq!(
+        *log.borrow(),
+        vec!["before", "after A", "before", "after B", "before", "after C"]
+    );
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    let mut m = machine(vec!["A".into()], "A", vec![]);
+    assert!(matches!(
+        m.add_ordered_transitions::<&str>(
+            None,
+            None,
+            true,
+            true,
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![]
+        ),
+        Err(Error::InsufficientStatesError)
+    ));
+    let mut m = abc("A");
+    let noop = || PotentialTriggers::from(TriggerFunction::new(|_| {}, None));
+    assert!(matches!(
+        m.add_ordered_transitions::<&str>(
+            None,
+            None,
+            false,
+            true,
+            vec![],
+            vec![],
+            vec![noop(), noop(), noop()],
+            vec![],
+            vec![]
+        ),
+        Err(Error::ArgumentsError)
+    ));
+    m.add_ordered_transitions::<&str>(
+        None,
+        None,
+        false,
+        true,
+        vec![],
+        vec![],
+        vec![noop(), noop()],
+        vec![],
+        vec![],
+    )
+    .unwrap();
+}
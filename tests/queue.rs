@@ -0,0 +1,88 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+fn chained(queued: bool, log: &Log, fail: bool) -> Machine {
+    let chain = |trigger: &'static str| {
+        let log = log.clone();
+        TriggerFunction::new(
+            move |ed: &EventData| {
+                log.borrow_mut().push(format!("after go start {}", trigger));
+                let result = ed.machine().trigger(ed.model(), trigger).unwrap();
+                log.borrow_mut()
+                    .push(format!("after go end {} {}", trigger, result));
+            },
+            None,
+        )
+    };
+    let mut after = vec![chain("next")];
+    if fail {
+        // once ``next`` has been processed, ``go`` is no longer valid
+        after.push(chain("go"));
+    }
+    builder(
+        vec![
+            "A".into(),
+            "B".into(),
+            State::new("C".into(), Some(vec![push(log, "enter C")]), None, None),
+        ],
+        "A",
+        vec![
+            TransitionParameters::new("go", &["A"], "B").after(after),
+            TransitionParameters::new("next", &["B"], "C").after(push(log, "after next")),
+            TransitionParameters::new("reset", &["C"], "A"),
+        ],
+    )
+    .queued(queued)
+    .build()
+}
+
+#[test]
+fn queued_triggers_run_after_the_current_transition() {
+    let log = log();
+    let m = chained(true, &log, false);
+    assert!(m.has_queue());
+    assert!(m.dispatch("go").unwrap());
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "after go start next",
+            "after go end next true",
+            "enter C",
+            "after next"
+        ]
+    );
+    assert_eq!(state_of(&m, m.model().unwrap()), "C");
+}
+
+#[test]
+fn unqueued_triggers_run_immediately() {
+    let log = log();
+    let m = chained(false, &log, false);
+    assert!(!m.has_queue());
+    assert!(m.dispatch("go").unwrap());
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "after go start next",
+            "enter C",
+            "after next",
+            "after go end next true"
+        ]
+    );
+}
+
+#[test]
+fn errors_clear_the_queue() {
+    let log = log();
+    let m = chained(true, &log, true);
+    let model = m.model().unwrap().clone();
+    assert!(matches!(
+        m.trigger(&model, "go"),
+        Err(Error::MachineError(msg)) if msg == "Can't trigger event go from state C!"
+    ));
+    assert_eq!(state_of(&m, &model), "C");
+    assert!(m.trigger(&model, "reset").unwrap());
+    assert_eq!(state_of(&m, &model), "A");
+}
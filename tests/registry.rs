@@ -0,0 +1,108 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+#[test]
+fn named_callables_are_resolved_when_triggered() {
+    let log = log();
+    let mut m = machine(
+        vec!["A".into(), "B".into()],
+        "A",
+        vec![
+            TransitionParameters::new("go", &["A"], "B")
+                .conditions("ok")
+                .after("notify"),
+            TransitionParameters::new("back", &["B"], "A").unless("ok"),
+        ],
+    );
+    m.register_callback("notify", push(&log, "notify")).unwrap();
+    m.register_condition("ok", ConditionFunction::new(|_| true, None))
+        .unwrap();
+    assert!(m.dispatch("go").unwrap());
+    assert!(!m.dispatch("back").unwrap());
+    assert_eq!(*log.borrow(), vec!["notify"]);
+    assert!(m.registry().callback("notify").is_some());
+    assert!(m.registry().condition("notify").is_none());
+}
+
+#[test]
+fn unknown_names_raise_callable_error() {
+    let m = machine(
+        vec!["A".into(), "B".into()],
+        "A",
+        vec![
+            TransitionParameters::new("go", &["A"], "B").before("missing"),
+            TransitionParameters::new("check", &["A"], "B").conditions("absent"),
+        ],
+    );
+    assert!(matches!(
+        m.dispatch("go"),
+        Err(Error::CallableError(name)) if name == "missing"
+    ));
+    assert!(matches!(
+        m.dispatch("check"),
+        Err(Error::CallableError(name)) if name == "absent"
+    ));
+    assert_eq!(state_of(&m, m.model().unwrap()), "A");
+}
+
+#[test]
+fn dynamic_methods_bind_by_name() {
+    let log = log();
+    let mut m = machine(vec!["A".into(), "B".into()], "A", vec![]);
+    m.register_callback("on_exit_A", push(&log, "exit A"))
+        .unwrap();
+    m.register_callback("before_go", push(&log, "before go"))
+        .unwrap();
+    m.add_transitions(vec![TransitionParameters::new("go", &["A"], "B")])
+        .unwrap();
+    m.register_callback("after_go", push(&log, "after go"))
+        .unwrap();
+    m.register_callback("after_go", push(&log, "after go"))
+        .unwrap();
+    m.register_callback("on_enter_B", push(&log, "enter B"))
+        .unwrap();
+    m.register_callback("prepare_", push(&log, "unbound"))
+        .unwrap();
+    m.register_callback("on_enter_X", push(&log, "unbound"))
+        .unwrap();
+    assert!(m.dispatch("go").unwrap());
+    assert_eq!(
+        *log.borrow(),
+        vec!["before go", "exit A", "enter B", "after go"]
+    );
+}
+
+#[test]
+fn dynamic_methods_bind_to_states_added_later_once() {
+    let log = log();
+    let mut m = machine(vec!["A".into()], "A", vec![]);
+    m.register_callback("on_enter_C", push(&log, "enter C"))
+        .unwrap();
+    m.register_callback("on_enter_D", push(&log, "enter D"))
+        .unwrap();
+    m.add_states(
+        vec![
+            "D".into(),
+            State::new(
+                "C".into(),
+                Some(vec![TriggerFunction::named("on_enter_C")]),
+                None,
+                None,
+            ),
+        ],
+        vec![],
+        vec![],
+        None,
+    )
+    .unwrap();
+    m.add_transitions(vec![
+        TransitionParameters::new("go", &["A"], "D"),
+        TransitionParameters::new("go", &["D"], "C"),
+    ])
+    .unwrap();
+    assert!(m.dispatch("go").unwrap());
+    assert!(m.dispatch("go").unwrap());
+    assert_eq!(*log.borrow(), vec!["enter D", "enter C"]);
+}
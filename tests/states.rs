@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+#[test]
+fn states_keep_insertion_order() {
+    let mut m = machine(vec!["C".into(), "A".into()], "C", vec![]);
+    m.add_states(vec!["B".into()], vec![], vec![], None)
+        .unwrap();
+    let names: Vec<&str> = m.states().map(|s| s.name()).collect();
+    assert_eq!(names, vec!["C", "A", "B"]);
+    assert_eq!(m.get_state("A").unwrap().name(), "A");
+}
+
+#[test]
+fn unknown_states_are_rejected() {
+    let m = machine(vec!["A".into()], "A", vec![]);
+    assert!(matches!(
+        m.get_state("X"),
+        Err(Error::RegisteredStateError(name)) if name == "X"
+    ));
+    assert!(m.set_state("X", None).is_err());
+    assert_eq!(state_of(&m, m.model().unwrap()), "A");
+}
+
+#[test]
+fn set_state_changes_all_models() {
+    let mut m = machine(vec!["A".into(), "B".into()], "A", vec![]);
+    let own = m.model().unwrap().clone();
+    let order: ModelRef = Order::shared();
+    m.add_model(order.clone(), None).unwrap();
+    assert!(m.model().is_none());
+    m.set_state("B", None).unwrap();
+    assert_eq!(state_of(&m, &own), "B");
+    assert_eq!(state_of(&m, &order), "B");
+    m.set_state("A", Some(&order)).unwrap();
+    assert_eq!(state_of(&m, &own), "B");
+    assert_eq!(state_of(&m, &order), "A");
+}
+
+#[test]
+fn add_states_applies_shared_defaults() {
+    let log = log();
+    let mut m = machine(vec!["A".into()], "A", vec![]);
+    m.add_states(
+        vec!["B".into(), State::new("C".into(), None, None, Some(false))],
+        vec![push(&log, "enter")],
+        vec![push(&log, "exit")],
+        Some(true),
+    )
+    .unwrap();
+    assert_eq!(m.get_state("A").unwrap().ignore_invalid_triggers(), None);
+    assert_eq!(
+        m.get_state("B").unwrap().ignore_invalid_triggers(),
+        Some(true)
+    );
+    assert_eq!(
+        m.get_state("C").unwrap().ignore_invalid_triggers(),
+        Some(false)
+    );
+    m.add_transitions(vec![
+        TransitionParameters::new("go", &["A"], "B"),
+        TransitionParameters::new("go", &["B"], "C"),
+    ])
+    .unwrap();
+    assert!(m.dispatch("go").unwrap());
+    assert!(m.dispatch("go").unwrap());
+    assert_eq!(*log.borrow(), vec!["enter", "exit", "enter"]);
+}
+
+#[test]
+fn add_states_without_override_keeps_machine_setting() {
+    let mut m = builder(vec!["A".into()], "A", vec![])
+        .send_event(false)
+        .ignore_invalid_triggers(true)
+        .build();
+    m.add_states(vec!["B".into()], vec![], vec![], None)
+        .unwrap();
+    assert_eq!(m.get_state("B").unwrap().ignore_invalid_triggers(), None);
+    assert!(!m.dispatch("nope").unwrap());
+}
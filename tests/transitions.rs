@@ -0,0 +1,116 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+
+fn logged(name: &str, log: &Log) -> State {
+    State::new(
+        name.into(),
+        Some(vec![push(log, &format!("enter {}", name))]),
+        Some(vec![push(log, &format!("exit {}", name))]),
+        None,
+    )
+}
+
+#[test]
+fn wildcard_source_covers_all_states() {
+    let log = log();
+    let mut m = machine(vec!["A".into(), "B".into()], "A", vec![]);
+    m.add_transitions(vec![TransitionParameters::new("reset", &["*"], "A")])
+        .unwrap();
+    m.add_transition(
+        "touch",
+        &["*"],
+        Some("="),
+        PotentialConditions::default(),
+        PotentialConditions::default(),
+        PotentialTriggers::default(),
+        PotentialTriggers::default(),
+        PotentialTriggers::default(),
+    )
+    .unwrap();
+    m.add_state(vec![logged("C", &log)], vec![], vec![], None)
+        .unwrap();
+    assert_eq!(m.get_triggers(&["A"]), vec!["reset", "touch"]);
+    assert_eq!(m.get_triggers(&["C"]), vec!["reset", "touch"]);
+    let model = m.model().unwrap().clone();
+    m.set_state("C", None).unwrap();
+    assert!(m.trigger(&model, "touch").unwrap());
+    assert_eq!(state_of(&m, &model), "C");
+    assert_eq!(*log.borrow(), vec!["exit C", "enter C"]);
+    assert!(m.trigger(&model, "reset").unwrap());
+    assert_eq!(state_of(&m, &model), "A");
+}
+
+#[test]
+fn same_destination_resolves_to_each_source() {
+    let log = log();
+    let mut m = machine(vec![logged("A", &log), logged("B", &log)], "A", vec![]);
+    m.add_transitions(vec![TransitionParameters::new("stay", &["A", "B"], "=")])
+        .unwrap();
+    let event = m.get_event("stay").unwrap();
+    assert_eq!(event.transitions("A")[0].dest(), Some("A"));
+    assert_eq!(event.transitions("B")[0].dest(), Some("B"));
+    assert!(m.dispatch("stay").unwrap());
+    assert_eq!(*log.borrow(), vec!["exit A", "enter A"]);
+}
+
+#[test]
+fn internal_transitions_keep_the_state() {
+    let log = log();
+    let mut m = machine(vec![logged("A", &log), "B".into()], "A", vec![]);
+    m.add_transitions(vec![TransitionParameters::internal("poke", &["A"])
+        .before(push(&log, "before"))
+        .after(push(&log, "after"))])
+        .unwrap();
+    m.add_transition(
+        "nudge",
+        &["*"],
+        None,
+        PotentialConditions::default(),
+        PotentialConditions::default(),
+        PotentialTriggers::default(),
+        PotentialTriggers::default(),
+        PotentialTriggers::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        m.get_event("poke").unwrap().transitions("A")[0].dest(),
+        None
+    );
+    assert!(m.dispatch("poke").unwrap());
+    assert!(m.dispatch("nudge").unwrap());
+    assert_eq!(state_of(&m, m.model().unwrap()), "A");
+    assert_eq!(*log.borrow(), vec!["before", "after"]);
+}
+
+#[test]
+fn removed_wildcard_transitions_are_not_added_to_new_states() {
+    let mut m = machine(
+        vec!["A".into(), "B".into(), "C".into()],
+        "A",
+        vec![
+            TransitionParameters::wildcard("go", "B"),
+            TransitionParameters::wildcard("go", "C"),
+        ],
+    );
+    m.remove_transition("go", None, Some("C")).unwrap();
+    m.add_states(vec!["D".into()], vec![], vec![], None)
+        .unwrap();
+    let dests: Vec<_> = m
+        .get_event("go")
+        .unwrap()
+        .transitions("D")
+        .iter()
+        .map(|t| t.dest())
+        .collect();
+    assert_eq!(dests, vec![Some("B")]);
+    m.remove_transition("go", Some("A"), None).unwrap();
+    m.add_states(vec!["E".into()], vec![], vec![], None)
+        .unwrap();
+    assert_eq!(m.get_event("go").unwrap().transitions("E").len(), 1);
+    m.remove_transition::<_, &str>("go", None, None).unwrap();
+    m.add_states(vec!["F".into()], vec![], vec![], None)
+        .unwrap();
+    assert!(m.get_event("go").is_err());
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn trigger_executes_transition() {
+    let log = log();
+    let m = machine(
+        vec![
+            "A".into(),
+            State::new("B".into(), Some(vec![push(&log, "enter B")]), None, None),
+        ],
+        "A",
+        vec![TransitionParameters::new("advance", &["A"], "B").after(push(&log, "after"))],
+    );
+    let model = m.model().unwrap().clone();
+    assert!(m.trigger(&model, "advance").unwrap());
+    assert_eq!(state_of(&m, &model), "B");
+    assert_eq!(*log.borrow(), vec!["enter B", "after"]);
+}
+
+#[test]
+fn trigger_stops_at_first_successful_transition() {
+    let log = log();
+    let m = machine(
+        vec!["A".into(), "B".into(), "C".into(), "D".into()],
+        "A",
+        vec![
+            TransitionParameters::new("advance", &["A"], "B")
+                .conditions(ConditionFunction::new(|_| false, None))
+                .prepare(push(&log, "prepare B")),
+            TransitionParameters::new("advance", &["A"], "C").prepare(push(&log, "prepare C")),
+            TransitionParameters::new("advance", &["A"], "D").prepare(push(&log, "prepare D")),
+        ],
+    );
+    assert!(m.dispatch("advance").unwrap());
+    assert_eq!(state_of(&m, m.model().unwrap()), "C");
+    assert_eq!(*log.borrow(), vec!["prepare B", "prepare C"]);
+}
+
+#[test]
+fn trigger_returns_false_when_no_transition_passes() {
+    let m = machine(
+        vec!["A".into(), "B".into()],
+        "A",
+        vec![TransitionParameters::new("advance", &["A"], "B")
+            .conditions(ConditionFunction::new(|_| false, None))],
+    );
+    assert!(!m.dispatch("advance").unwrap());
+    assert_eq!(state_of(&m, m.model().unwrap()), "A");
+}
+
+#[test]
+fn trigger_rejects_invalid_and_unknown_events() {
+    let m = machine(
+        vec!["A".into(), "B".into()],
+        "A",
+        vec![TransitionParameters::new("advance", &["A"], "B")],
+    );
+    let model = m.model().unwrap().clone();
+    assert!(m.trigger(&model, "advance").unwrap());
+    assert!(matches!(
+        m.trigger(&model, "advance"),
+        Err(Error::MachineError(msg)) if msg == "Can't trigger event advance from state B!"
+    ));
+    assert!(matches!(
+        m.trigger(&model, "nope"),
+        Err(Error::UnknownEventError(name)) if name == "nope"
+    ));
+}
+
+#[test]
+fn event_trigger_uses_machine() {
+    let m = machine(
+        vec!["A".into(), "B".into()],
+        "A",
+        vec![TransitionParameters::new("advance", &["A"], "B")],
+    );
+    let model = m.model().unwrap().clone();
+    let event = m.get_event("advance").unwrap();
+    assert!(event.trigger(&m, &model, EventArgs::new()).unwrap());
+    assert_eq!(state_of(&m, &model), "B");
+}
+
+#[test]
+fn may_trigger_checks_without_side_effects() {
+    let ready = Rc::new(RefCell::new(false));
+    let check = ready.clone();
+    let log = log();
+    let m = machine(
+        vec![
+            State::new("A".into(), None, Some(vec![push(&log, "exit A")]), None),
+            "B".into(),
+        ],
+        "A",
+        vec![TransitionParameters::new("go", &["A"], "B")
+            .prepare(push(&log, "prepare"))
+            .conditions(ConditionFunction::new(move |_| *check.borrow(), None))
+            .after(push(&log, "after"))],
+    );
+    let model = m.model().unwrap().clone();
+    assert!(!m.may_trigger(&model, "go").unwrap());
+    *ready.borrow_mut() = true;
+    assert!(m.may_trigger(&model, "go").unwrap());
+    assert!(!m.may_trigger(&model, "nope").unwrap());
+    assert_eq!(state_of(&m, &model), "A");
+    assert_eq!(*log.borrow(), vec!["prepare", "prepare"]);
+}
+
+#[test]
+fn may_trigger_sees_arguments_and_unless() {
+    let m = machine(
+        vec!["A".into(), "B".into()],
+        "A",
+        vec![
+            TransitionParameters::new("ship", &["A"], "B").unless(ConditionFunction::new(
+                |ed: &EventData| ed.args().kwarg::<bool>("hold").is_some(),
+                None,
+            )),
+        ],
+    );
+    let model = m.model().unwrap().clone();
+    assert!(m.may_trigger(&model, "ship").unwrap());
+    let hold = EventArgs::new().with_kwarg("hold", true);
+    assert!(!m.may_trigger_with_args(&model, "ship", hold).unwrap());
+    m.set_state("B", None).unwrap();
+    assert!(!m.may_trigger(&model, "ship").unwrap());
+}
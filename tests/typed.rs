@@ -0,0 +1,189 @@
+mod common;
+
+use common::*;
+use rustitions::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, strum_macros::AsRefStr, strum_macros::EnumString)]
+enum Phase {
+    Solid,
+    Liquid,
+    Gas,
+}
+
+fn phases() -> Machine<Phase> {
+    Builder::new(
+        vec![Phase::Solid.into(), Phase::Liquid.into(), Phase::Gas.into()],
+        Phase::Solid.into(),
+        vec![
+            TransitionParameters::new("melt", &[Phase::Solid], Phase::Liquid),
+            TransitionParameters::new("evaporate", &[Phase::Liquid], Phase::Gas),
+        ],
+    )
+    .send_event(false)
+    .auto_transitions(true)
+    .build()
+}
+
+#[test]
+fn enum_states_are_used_throughout() {
+    let m = phases();
+    let model = m.model().unwrap().clone();
+    assert!(m.dispatch("melt").unwrap());
+    assert!(m.is_state(Phase::Liquid, &model));
+    assert_eq!(m.get_model_state(&model).unwrap().value(), &Phase::Liquid);
+    assert_eq!(m.get_state(Phase::Gas).unwrap().name(), "Gas");
+    assert_eq!(
+        m.get_triggers(&[Phase::Liquid]),
+        vec!["to_Solid", "to_Liquid", "to_Gas", "evaporate"]
+    );
+    assert!(m.trigger(&model, "to_Solid").unwrap());
+    assert_eq!(m.models_in_state(Phase::Solid).len(), 1);
+    m.set_state(Phase::Gas, None).unwrap();
+    assert!(m.is_state(Phase::Gas, &model));
+}
+
+#[test]
+fn enum_states_parse_from_names() {
+    let state: State<Phase> = "Gas".parse().unwrap();
+    assert_eq!(state.value(), &Phase::Gas);
+    assert!("Plasma".parse::<State<Phase>>().is_err());
+    let state: State = "X".parse().unwrap();
+    assert_eq!(state.name(), "X");
+}
+
+#[test]
+fn wildcard_and_reflexive_transitions_take_enum_states() {
+    let mut m = phases();
+    m.add_transitions(vec![
+        TransitionParameters::wildcard("reset", Phase::Solid),
+        TransitionParameters::reflexive("stay", &[Phase::Gas]),
+    ])
+    .unwrap();
+    let model = m.model().unwrap().clone();
+    assert!(m.trigger(&model, "to_Gas").unwrap());
+    assert!(m.trigger(&model, "stay").unwrap());
+    assert!(m.is_state(Phase::Gas, &model));
+    assert!(m.trigger(&model, "reset").unwrap());
+    assert!(m.is_state(Phase::Solid, &model));
+}
+
+#[test]
+fn ordered_and_removed_transitions_take_enum_states() {
+    let mut m = phases();
+    m.add_ordered_transitions(
+        Some(&[Phase::Gas, Phase::Liquid, Phase::Solid]),
+        Some("cool".into()),
+        false,
+        true,
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(
+        m.get_triggers(&[Phase::Gas]),
+        vec!["to_Solid", "to_Liquid", "to_Gas", "cool"]
+    );
+    m.remove_transition("cool", Some(Phase::Gas), None).unwrap();
+    m.remove_transition("melt", None, Some(Phase::Liquid))
+        .unwrap();
+    assert!(m.get_triggers(&[Phase::Gas]).iter().all(|t| *t != "cool"));
+    assert!(m.get_triggers(&[Phase::Solid]).contains(&"cool"));
+    assert!(m.get_event("melt").is_err());
+}
+
+#[test]
+fn transitions_to_unknown_states_are_rejected() {
+    let mut m = machine(vec!["A".into()], "A", vec![]);
+    assert!(matches!(
+        m.add_transitions(vec![TransitionParameters::new("go", &["A"], "Nope")]),
+        Err(Error::UnknownStateError(state)) if state == "Nope"
+    ));
+    assert!(matches!(
+        m.add_transitions(vec![TransitionParameters::new("go", &["Nope"], "A")]),
+        Err(Error::UnknownStateError(state)) if state == "Nope"
+    ));
+    assert!(m.get_event("go").map_or(true, |event| event.is_empty()));
+}
+
+#[derive(Debug, Clone, strum_macros::AsRefStr, strum_macros::EnumVariantNames)]
+#[strum(serialize_all = "snake_case")]
+enum Checkout {
+    Pay { amount: u32 },
+    Ship,
+    Refund,
+}
+
+impl EventValue for Checkout {
+    fn event_names() -> &'static [&'static str] {
+        use strum::VariantNames;
+        Self::VARIANTS
+    }
+}
+
+fn checkout(paid: Rc<RefCell<u32>>) -> Machine<String, Checkout> {
+    Builder::new(
+        vec!["open".into(), "paid".into(), "shipped".into()],
+        "open".into(),
+        vec![
+            TransitionParameters::new(Checkout::Pay { amount: 0 }, &["open"], "paid").after(
+                TriggerFunction::new(
+                    move |ed: &EventData<String, Checkout>| {
+                        if let Some(Checkout::Pay { amount }) = ed.event_value() {
+                            *paid.borrow_mut() += amount;
+                        }
+                    },
+                    None,
+                ),
+            ),
+            TransitionParameters::new(Checkout::Ship, &["paid"], "shipped"),
+        ],
+    )
+    .auto_transitions(true)
+    .build()
+}
+
+#[test]
+fn typed_events_carry_their_value() {
+    let paid = Rc::new(RefCell::new(0));
+    let m = checkout(paid.clone());
+    let model = m.model().unwrap().clone();
+    assert_eq!(m.get_triggers(&["open"]), vec!["pay"]);
+    assert!(m.may_trigger(&model, Checkout::Pay { amount: 1 }).unwrap());
+    assert_eq!(*paid.borrow(), 0);
+    assert!(m.trigger(&model, Checkout::Pay { amount: 42 }).unwrap());
+    assert_eq!(*paid.borrow(), 42);
+    assert!(m.dispatch(Checkout::Ship).unwrap());
+    assert!(m.is_state("shipped", &model));
+}
+
+#[test]
+fn typed_events_without_transitions_are_known() {
+    let mut m = checkout(Rc::new(RefCell::new(0)));
+    let model = m.model().unwrap().clone();
+    assert!(matches!(
+        m.trigger(&model, Checkout::Refund),
+        Err(Error::MachineError(msg)) if msg == "Can't trigger event refund from state open!"
+    ));
+    m.remove_transition::<_, &str>(Checkout::Ship, None, None)
+        .unwrap();
+    assert!(m.get_event("ship").unwrap().is_empty());
+    assert!(!m.may_trigger(&model, Checkout::Ship).unwrap());
+}
+
+#[test]
+fn typed_events_reject_other_names() {
+    let mut m = checkout(Rc::new(RefCell::new(0)));
+    assert!(matches!(
+        m.add_transitions(vec![TransitionParameters {
+            trigger: "cancel".into(),
+            ..TransitionParameters::new(Checkout::Refund, &["paid"], "open")
+        }]),
+        Err(Error::UnknownEventError(name)) if name == "cancel"
+    ));
+    assert!(m.get_event("to_open").is_err());
+}